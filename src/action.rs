@@ -0,0 +1,18 @@
+use crate::data::CellPos;
+
+/// A player input that changes the state of a round.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq, Eq)]
+pub enum GameAction {
+    Reveal(CellPos),
+    ToggleFlag(CellPos),
+    /// Reveal every unflagged neighbour of a number whose flag count is satisfied.
+    Chord(CellPos),
+}
+
+impl GameAction {
+    pub fn position(&self) -> &CellPos {
+        match self {
+            Self::Reveal(pos) | Self::ToggleFlag(pos) | Self::Chord(pos) => pos,
+        }
+    }
+}
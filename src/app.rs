@@ -1,5 +1,17 @@
-
-use crate::data::{GlobalState, RoundData};
+use crate::bindings::{BINDINGS_KEY, InputBindings};
+use crate::config::{BoardConfig, ConfigError};
+use crate::data::{GameSettings, GlobalState, RoundData, RoundState, SETTINGS_KEY, TOUCH_MODE_KEY};
+use crate::i18n::{LOCALE_KEY, Locale, tr, tr_args};
+use crate::leaderboard::{LEADERBOARD_KEY, Leaderboard};
+use crate::metrics::RoundMetrics;
+use crate::replay::{Replay, ReplayFormat, ReplayPlayer};
+use crate::save::slots::SaveSlots;
+use crate::scenes::SceneType;
+use crate::scenes::save_slots::{SlotExport, SlotRequest};
+use crate::share::SharedBoard;
+use crate::skin::{SKIN_KEY, SkinLibrary};
+use crate::statistics::{Difficulty, PlayerStatistics, STATISTICS_KEY};
+use crate::theme::{APPEARANCE_KEY, Appearance, Theme};
 use log::debug;
 
 #[derive(Clone)]
@@ -10,31 +22,39 @@ pub struct GameImageSource {
     pub cell_flag: egui::ImageSource<'static>,
     pub cell_mine: egui::ImageSource<'static>,
     pub cell_mine_red: egui::ImageSource<'static>,
+    pub cell_mine_wrong: egui::ImageSource<'static>,
+    pub face_unpressed: egui::ImageSource<'static>,
+    pub face_pressed: egui::ImageSource<'static>,
+    pub face_active: egui::ImageSource<'static>,
+    pub face_win: egui::ImageSource<'static>,
+    pub face_lose: egui::ImageSource<'static>,
 }
 impl GameImageSource {}
 impl Default for GameImageSource {
     fn default() -> Self {
-        let mut cell_num = vec![];
-        cell_num.push(egui::include_image!("../assets/game/type0.svg"));
-        cell_num.push(egui::include_image!("../assets/game/type1.svg"));
-        cell_num.push(egui::include_image!("../assets/game/type2.svg"));
-        cell_num.push(egui::include_image!("../assets/game/type3.svg"));
-        cell_num.push(egui::include_image!("../assets/game/type4.svg"));
-        cell_num.push(egui::include_image!("../assets/game/type5.svg"));
-        cell_num.push(egui::include_image!("../assets/game/type6.svg"));
-        cell_num.push(egui::include_image!("../assets/game/type7.svg"));
-        cell_num.push(egui::include_image!("../assets/game/type8.svg"));
-
-        let mut cell_num_yellow = vec![];
-        cell_num_yellow.push(egui::include_image!("../assets/game/type0.svg"));
-        cell_num_yellow.push(egui::include_image!("../assets/game/type1_yellow.svg"));
-        cell_num_yellow.push(egui::include_image!("../assets/game/type2_yellow.svg"));
-        cell_num_yellow.push(egui::include_image!("../assets/game/type3_yellow.svg"));
-        cell_num_yellow.push(egui::include_image!("../assets/game/type4_yellow.svg"));
-        cell_num_yellow.push(egui::include_image!("../assets/game/type5_yellow.svg"));
-        cell_num_yellow.push(egui::include_image!("../assets/game/type6_yellow.svg"));
-        cell_num_yellow.push(egui::include_image!("../assets/game/type7_yellow.svg"));
-        cell_num_yellow.push(egui::include_image!("../assets/game/type8_yellow.svg"));
+        let cell_num = vec![
+            egui::include_image!("../assets/game/type0.svg"),
+            egui::include_image!("../assets/game/type1.svg"),
+            egui::include_image!("../assets/game/type2.svg"),
+            egui::include_image!("../assets/game/type3.svg"),
+            egui::include_image!("../assets/game/type4.svg"),
+            egui::include_image!("../assets/game/type5.svg"),
+            egui::include_image!("../assets/game/type6.svg"),
+            egui::include_image!("../assets/game/type7.svg"),
+            egui::include_image!("../assets/game/type8.svg"),
+        ];
+
+        let cell_num_yellow = vec![
+            egui::include_image!("../assets/game/type0.svg"),
+            egui::include_image!("../assets/game/type1_yellow.svg"),
+            egui::include_image!("../assets/game/type2_yellow.svg"),
+            egui::include_image!("../assets/game/type3_yellow.svg"),
+            egui::include_image!("../assets/game/type4_yellow.svg"),
+            egui::include_image!("../assets/game/type5_yellow.svg"),
+            egui::include_image!("../assets/game/type6_yellow.svg"),
+            egui::include_image!("../assets/game/type7_yellow.svg"),
+            egui::include_image!("../assets/game/type8_yellow.svg"),
+        ];
 
         Self {
             cell_num,
@@ -43,19 +63,43 @@ impl Default for GameImageSource {
             cell_closed: egui::include_image!("../assets/game/closed.svg"),
             cell_mine: egui::include_image!("../assets/game/mine.svg"),
             cell_mine_red: egui::include_image!("../assets/game/mine_red.svg"),
+            cell_mine_wrong: egui::include_image!("../assets/game/mine_wrong.svg"),
+            face_unpressed: egui::include_image!("../assets/game/face_unpressed.svg"),
+            face_pressed: egui::include_image!("../assets/game/face_pressed.svg"),
+            face_active: egui::include_image!("../assets/game/face_active.svg"),
+            face_win: egui::include_image!("../assets/game/face_win.svg"),
+            face_lose: egui::include_image!("../assets/game/face_lose.svg"),
         }
     }
 }
 
-/// We derive Deserialize/Serialize so we can persist app state on shutdown.
-#[derive(serde::Deserialize, serde::Serialize)]
-#[serde(default)] // if we add new fields, give them default values when deserializing old state
+/// The whole game. What outlives a session is saved field by field in
+/// [`eframe::App::save`].
 #[derive(Default)]
 pub struct MineSweeper {
     global_state: GlobalState,
     round: RoundData,
-    #[serde(skip)]
-    image_sources: GameImageSource,
+    skins: SkinLibrary,
+    appearance: Appearance,
+    /// The language picked in the settings, or `None` to follow the system.
+    locale: Option<Locale>,
+    /// Detected once at startup.
+    system_locale: Locale,
+    replay_player: Option<ReplayPlayer>,
+    statistics: PlayerStatistics,
+    leaderboard: Leaderboard,
+    save_slots: SaveSlots,
+    bindings: InputBindings,
+    /// Settings applied from the settings scene, to start a round with after drawing it.
+    apply_settings: Option<GameSettings>,
+    /// Carried out after the scene is drawn, when storage is at hand.
+    slot_request: Option<SlotRequest>,
+    /// The last slot export, reported in the slot list.
+    slot_export: Option<SlotExport>,
+    /// Start time of the round that just set a personal best, while its banner shows.
+    personal_best: Option<i64>,
+    /// Why the last dropped file could not be opened, shown until dismissed.
+    import_error: Option<String>,
 }
 
 impl MineSweeper {
@@ -64,45 +108,383 @@ impl MineSweeper {
         // This is also where you can customize the look and feel of egui using
         // `cc.egui_ctx.set_visuals` and `cc.egui_ctx.set_fonts`.
 
-        // Load previous app state (if any).
+        // Resume the round from the last session (if any).
         // Note that you must enable the `persistence` feature for this to work.
-        // if let Some(storage) = cc.storage {
-        //     eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default()
-        // } else {
-        //     Default::default()
-        // }
-        Default::default()
+        let round = cc
+            .storage
+            .map(crate::save::load)
+            .transpose()
+            .unwrap_or_else(|err| {
+                log::warn!("Starting a new round: {err}");
+                None
+            })
+            .flatten()
+            .unwrap_or_default();
+        let mut global_state = GlobalState::default();
+        if let Some(settings) = cc
+            .storage
+            .and_then(|storage| eframe::get_value(storage, SETTINGS_KEY))
+        {
+            global_state.game_settings = settings;
+        }
+        if let Some(touch_mode) = cc
+            .storage
+            .and_then(|storage| eframe::get_value(storage, TOUCH_MODE_KEY))
+        {
+            global_state.touch_mode = touch_mode;
+        }
+        Self {
+            global_state,
+            round,
+            statistics: cc
+                .storage
+                .and_then(|storage| eframe::get_value(storage, STATISTICS_KEY))
+                .unwrap_or_default(),
+            leaderboard: cc
+                .storage
+                .and_then(|storage| eframe::get_value(storage, LEADERBOARD_KEY))
+                .unwrap_or_default(),
+            save_slots: cc.storage.map(SaveSlots::load).unwrap_or_default(),
+            bindings: cc
+                .storage
+                .and_then(|storage| eframe::get_value(storage, BINDINGS_KEY))
+                .unwrap_or_default(),
+            appearance: cc
+                .storage
+                .and_then(|storage| eframe::get_value(storage, APPEARANCE_KEY))
+                .unwrap_or_default(),
+            locale: cc
+                .storage
+                .and_then(|storage| eframe::get_value(storage, LOCALE_KEY))
+                .flatten(),
+            system_locale: Locale::detect(),
+            skins: SkinLibrary::new(
+                cc.storage
+                    .and_then(|storage| eframe::get_value(storage, SKIN_KEY))
+                    .flatten(),
+            ),
+            ..Default::default()
+        }
     }
     pub fn stop_game() {}
+
+    /// Starts a new round on a board opened from a link.
+    pub fn start_shared_board(&mut self, board: &SharedBoard) {
+        self.round = board.new_round();
+        self.global_state.change_scene(SceneType::Game);
+    }
+
+    /// Switches the settings to `board`, keeping the other options, and starts a round on it.
+    ///
+    /// # Errors
+    ///
+    /// Fails when the saved options ask for a safe first click the board has no room for.
+    pub fn start_custom_board(&mut self, board: BoardConfig) -> Result<(), ConfigError> {
+        let settings = &self.global_state.game_settings;
+        let settings = GameSettings::new(
+            board,
+            settings.safe_first_click(),
+            settings.single_click_chord(),
+        )?;
+        self.round = settings.new_round();
+        self.global_state.game_settings = settings;
+        self.global_state.change_scene(SceneType::Game);
+        Ok(())
+    }
+
+    /// Counts the round in the statistics once it has ended, and enters unassisted wins on
+    /// the leaderboard.
+    fn record_finished_round(&mut self, frame: &mut eframe::Frame) {
+        let round = &mut self.round;
+        if round.recorded
+            || round.replay.events.is_empty()
+            || !matches!(round.round_state_type, RoundState::Ended(_))
+        {
+            return;
+        }
+        round.recorded = true;
+        if let Some(name) = &round.slot {
+            match frame.storage_mut() {
+                Some(storage) => self.save_slots.mark_recorded(storage, name),
+                None => log::warn!("No storage to mark {name} as recorded in"),
+            }
+        }
+        let difficulty =
+            Difficulty::classify(round.board_width, round.board_height, round.total_mine);
+        let metrics = RoundMetrics::from_replay(&round.replay);
+        self.statistics.record(difficulty, &metrics);
+        if !metrics.won || round.assisted {
+            return;
+        }
+        let rank = self
+            .leaderboard
+            .submit(difficulty, round.start_time, &round.replay, &metrics);
+        if rank.is_some_and(|rank| self.leaderboard.is_personal_best(difficulty, rank)) {
+            self.personal_best = Some(round.start_time);
+        }
+    }
+
+    /// Renders the current scene into `rect`.
+    fn show_scene(&mut self, ui: &mut egui::Ui, rect: egui::Rect) {
+        match (&self.global_state.current_scene, &mut self.replay_player) {
+            (SceneType::Replay, Some(player)) => {
+                ui.put(rect, crate::scenes::ReplayScene::new(rect, player));
+            }
+            (SceneType::SaveSlots, _) => {
+                ui.put(
+                    rect,
+                    crate::scenes::SaveSlotsScene::new(
+                        rect,
+                        &self.save_slots,
+                        &mut self.slot_request,
+                        self.slot_export.as_ref(),
+                    ),
+                );
+            }
+            (SceneType::Setting, _) => {
+                ui.put(
+                    rect,
+                    crate::scenes::SettingScene::new(
+                        rect,
+                        &self.global_state.game_settings,
+                        &mut self.bindings,
+                        &mut self.skins,
+                        &mut self.appearance,
+                        &mut self.locale,
+                        &mut self.apply_settings,
+                    ),
+                );
+                if let Some(settings) = self.apply_settings.take() {
+                    self.round = settings.new_round();
+                    self.global_state.game_settings = settings;
+                    self.global_state.change_scene(SceneType::Game);
+                }
+            }
+            (SceneType::Statistics, _) => {
+                ui.put(
+                    rect,
+                    crate::scenes::StatisticsScene::new(rect, &mut self.statistics),
+                );
+            }
+            (SceneType::PlayerStanding, _) => {
+                let mut watch = None;
+                let difficulty = Difficulty::classify(
+                    self.round.board_width,
+                    self.round.board_height,
+                    self.round.total_mine,
+                );
+                ui.put(
+                    rect,
+                    crate::scenes::LeaderboardScene::new(
+                        rect,
+                        &mut self.leaderboard,
+                        difficulty,
+                        &mut watch,
+                    ),
+                );
+                if let Some(replay) = watch {
+                    self.watch_replay(replay);
+                }
+            }
+            _ => {
+                ui.put(rect, crate::scenes::GameScene::new(rect, &mut self.round));
+                if self.personal_best == Some(self.round.start_time) {
+                    paint_personal_best_banner(ui, rect);
+                }
+            }
+        }
+    }
+
+    fn handle_slot_request(&mut self, frame: &mut eframe::Frame) {
+        let Some(request) = self.slot_request.take() else {
+            return;
+        };
+        match request {
+            SlotRequest::Load(name) => {
+                if let Some(Ok(saved)) = self.save_slots.get(&name).map(|it| &it.round) {
+                    self.round = saved.clone().resume();
+                    self.round.fixed_layout = true;
+                    // Reloading until a guess works is help, like undo.
+                    self.round.assisted = true;
+                    self.round.slot = Some(name);
+                    self.global_state.change_scene(SceneType::Game);
+                }
+            }
+            SlotRequest::Export(name) => {
+                if let Some(Ok(saved)) = self.save_slots.get(&name).map(|it| &it.round) {
+                    let result = export_saved_round(&name, saved);
+                    self.slot_export = Some(SlotExport { name, result });
+                }
+            }
+            SlotRequest::Save(name) => match frame.storage_mut() {
+                Some(storage) => self.save_slots.save(storage, &name, &self.round),
+                None => log::warn!("No storage to save {name} in"),
+            },
+            SlotRequest::Delete(name) => match frame.storage_mut() {
+                Some(storage) => self.save_slots.delete(storage, &name),
+                None => log::warn!("No storage to delete {name} from"),
+            },
+        }
+    }
+
+    /// Opens a replay picked outside the game, such as from the leaderboard.
+    fn watch_replay(&mut self, replay: Replay) {
+        self.replay_player = Some(ReplayPlayer::new(replay));
+        self.global_state.change_scene(SceneType::Replay);
+    }
+
+    /// Loads the current round into the replay viewer when entering it, and drops it on leave.
+    fn sync_replay_player(&mut self) {
+        if self.global_state.current_scene == SceneType::Replay {
+            if self.replay_player.is_none() {
+                self.replay_player = Some(ReplayPlayer::new(self.round.replay.clone()));
+            }
+        } else {
+            self.replay_player = None;
+        }
+    }
+
+    /// Opens `.avf` and `.rawvf` files dropped onto the window in the replay viewer,
+    /// `.board` layouts and exported saves as a new round, and installs zipped skin packs.
+    /// Why a file could not be opened is shown over the scene until dismissed.
+    fn import_dropped_replays(&mut self, ctx: &egui::Context) {
+        let dropped_files = ctx.input(|i| i.raw.dropped_files.clone());
+        for file in dropped_files {
+            let name = file
+                .path
+                .as_ref()
+                .and_then(|it| it.file_name())
+                .map_or_else(|| file.name.clone(), |it| it.to_string_lossy().into_owned());
+            let data = match (&file.bytes, &file.path) {
+                (Some(bytes), _) => bytes.to_vec(),
+                (None, Some(path)) => match std::fs::read(path) {
+                    Ok(data) => data,
+                    Err(err) => {
+                        self.import_failed(&name, &err);
+                        continue;
+                    }
+                },
+                (None, None) => continue,
+            };
+            if crate::skin::is_zip(&name) {
+                self.skins.install(ctx, &name, data);
+                continue;
+            }
+            if name.to_ascii_lowercase().ends_with(".board") {
+                match crate::board_format::read(&String::from_utf8_lossy(&data)) {
+                    Ok(board) => {
+                        self.round = RoundData::from_board_state(board);
+                        self.global_state.change_scene(SceneType::Game);
+                        self.import_error = None;
+                    }
+                    Err(err) => self.import_failed(&name, &err),
+                }
+                continue;
+            }
+            if name
+                .to_ascii_lowercase()
+                .ends_with(&format!(".{}", crate::save::SAVE_FILE_EXTENSION))
+            {
+                match crate::save::decode(&String::from_utf8_lossy(&data)) {
+                    Ok(saved) => {
+                        self.round = saved.resume();
+                        self.round.fixed_layout = true;
+                        self.round.assisted = true;
+                        self.round.slot = None;
+                        self.global_state.change_scene(SceneType::Game);
+                        self.import_error = None;
+                    }
+                    Err(err) => self.import_failed(&name, &err),
+                }
+                continue;
+            }
+            match ReplayFormat::import(&name, &data) {
+                Ok(replay) => {
+                    self.watch_replay(replay);
+                    self.import_error = None;
+                }
+                Err(err) => self.import_failed(&name, &err),
+            }
+        }
+    }
+    fn import_failed(&mut self, name: &str, err: &dyn std::fmt::Display) {
+        log::warn!("Failed to open {name}: {err}");
+        self.import_error = Some(tr_args(
+            "app.import_failed",
+            &[("file", &name), ("error", err)],
+        ));
+    }
+    /// Shows why a dropped file could not be opened over whichever scene is open.
+    fn show_import_error(&mut self, ctx: &egui::Context) {
+        let Some(message) = &self.import_error else {
+            return;
+        };
+        let mut dismissed = false;
+        egui::Area::new(egui::Id::new("IMPORT_ERROR"))
+            .anchor(egui::Align2::CENTER_TOP, egui::vec2(0.0, 8.0))
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.set_max_width(self.global_state.content_size.0 * 0.9);
+                    ui.colored_label(ui.visuals().error_fg_color, message);
+                    dismissed = ui.button(tr("app.dismiss")).clicked();
+                });
+            });
+        if dismissed {
+            self.import_error = None;
+        }
+    }
 }
 
 impl eframe::App for MineSweeper {
     /// Called by the framework to save state before shutdown.
+    ///
+    /// The round and each kind of preference or record go under keys of their own, so
+    /// one that no longer parses after an update does not reset the others.
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
-        eframe::set_value(storage, eframe::APP_KEY, self);
+        crate::save::save(storage, &self.round);
+        eframe::set_value(storage, SETTINGS_KEY, &self.global_state.game_settings);
+        eframe::set_value(storage, TOUCH_MODE_KEY, &self.global_state.touch_mode);
+        eframe::set_value(storage, STATISTICS_KEY, &self.statistics);
+        eframe::set_value(storage, LEADERBOARD_KEY, &self.leaderboard);
+        eframe::set_value(storage, BINDINGS_KEY, &self.bindings);
+        eframe::set_value(storage, SKIN_KEY, &self.skins.selected());
+        eframe::set_value(storage, APPEARANCE_KEY, &self.appearance);
+        eframe::set_value(storage, LOCALE_KEY, &self.locale);
     }
 
     /// Called each time the UI needs repainting, which may be many times per second.
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        let locale = Some(self.locale.unwrap_or(self.system_locale))
+            .filter(|it| it.has_font())
+            .unwrap_or_default();
+        locale.set_current();
+        crate::i18n::ensure_fonts(ctx, locale);
+
         // Global Style
+        let theme = self.appearance.resolve(ctx.system_theme());
+        theme.store(ctx);
+        self.appearance.motion.store(ctx);
         ctx.style_mut(|style| {
+            style.visuals = theme.visuals();
             style.spacing.window_margin = egui::Margin::ZERO;
             style.spacing.item_spacing = egui::Vec2::ZERO;
-            style.visuals.window_stroke = egui::Stroke::NONE;
             // style.debug.show_widget_hits = true;
             // style.debug.debug_on_hover = true;
             style.spacing.interact_size = egui::Vec2::ZERO;
             style.spacing.indent = 0.0;
         });
 
+        self.import_dropped_replays(ctx);
+
         // Insert nessecery data for nested UI to render
         ctx.data_mut(|d| d.insert_temp(egui::Id::NULL, self.global_state.clone()));
         ctx.data_mut(|d| {
-            d.insert_temp(egui::Id::new("IMAGE_SOURCE"), self.image_sources.clone());
+            d.insert_temp(egui::Id::new("IMAGE_SOURCE"), self.skins.images().clone());
+            d.insert_temp(egui::Id::new("INPUT_BINDINGS"), self.bindings.clone());
         });
 
         egui::CentralPanel::default()
-            .frame(egui::Frame::NONE.fill(egui::Color32::from_rgb(198, 198, 198)))
+            .frame(egui::Frame::NONE.fill(theme.background))
             .show(ctx, |ui| {
                 // // Resize Logic
                 let current_window_size = ui.available_size();
@@ -158,20 +540,57 @@ impl eframe::App for MineSweeper {
                 egui::CentralPanel::default()
                     .frame(egui::Frame::NONE)
                     .show(ctx, |ui| {
-                        let main_scene = match &self.round.round_state_type {
-                            crate::data::RoundState::NotStarted
-                            | crate::data::RoundState::Playing => {
-                                crate::scenes::GameScene::new(main_scene_rect, &mut self.round)
-                            }
-                            crate::data::RoundState::Ended(round_ending_type) => {
-                                crate::scenes::GameScene::new(main_scene_rect, &mut self.round)
-                            }
+                        self.sync_replay_player();
+                        self.show_scene(ui, main_scene_rect);
+                        self.record_finished_round(frame);
+                        let replay = match &self.replay_player {
+                            Some(player) => &player.replay,
+                            None => &self.round.replay,
                         };
-                        let main_scene = ui.put(main_scene_rect, main_scene);
-                        let function_panel = crate::widgets::FunctionPanel::new();
+                        let function_panel =
+                            crate::widgets::FunctionPanel::new(&mut self.global_state, replay);
                         ui.put(function_panel_rect, function_panel);
                     })
             });
-        ctx.request_repaint();
+        self.show_import_error(ctx);
+        self.handle_slot_request(frame);
     }
 }
+
+/// Writes the saved round into the export folder.
+fn export_saved_round(
+    name: &str,
+    saved: &crate::save::SavedRound,
+) -> Result<std::path::PathBuf, crate::export::ExportError> {
+    let text = ron::ser::to_string_pretty(saved, ron::ser::PrettyConfig::default())
+        .map_err(|err| crate::export::ExportError::Io(err.to_string()))?;
+    let result = crate::export::write(
+        &crate::export::file_stem(name),
+        crate::save::SAVE_FILE_EXTENSION,
+        text.as_bytes(),
+    );
+    match &result {
+        Ok(path) => log::info!("Exported {name} to {}", path.display()),
+        Err(err) => log::warn!("Failed to export {name}: {err}"),
+    }
+    result
+}
+
+/// Shown over the board after a round that beat the player's best time.
+fn paint_personal_best_banner(ui: &egui::Ui, scene_rect: egui::Rect) {
+    let board_top = scene_rect.max.y - scene_rect.width();
+    let banner_rect = egui::Rect::from_center_size(
+        egui::pos2(scene_rect.center().x, board_top + scene_rect.width() * 0.1),
+        egui::vec2(scene_rect.width() * 0.6, scene_rect.width() * 0.1),
+    );
+    let theme = Theme::load(ui.ctx());
+    let painter = ui.painter();
+    painter.rect_filled(banner_rect, banner_rect.height() * 0.2, theme.banner);
+    painter.text(
+        banner_rect.center(),
+        egui::Align2::CENTER_CENTER,
+        tr("app.personal_best"),
+        egui::FontId::proportional(banner_rect.height() * 0.5),
+        theme.celebration,
+    );
+}
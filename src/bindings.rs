@@ -0,0 +1,458 @@
+//! Which mouse buttons and keys trigger each game action.
+
+use std::collections::BTreeMap;
+
+use crate::i18n::{tr, tr_args};
+
+/// Storage key of the [`InputBindings`].
+pub const BINDINGS_KEY: &str = "bindings";
+
+/// A game action that can be bound to mouse buttons and keys.
+#[derive(
+    serde::Deserialize, serde::Serialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord,
+)]
+pub enum BoundAction {
+    Reveal,
+    Flag,
+    Chord,
+    Restart,
+    Hint,
+    Undo,
+    Pause,
+}
+
+impl BoundAction {
+    pub const ALL: [Self; 7] = [
+        Self::Reveal,
+        Self::Flag,
+        Self::Chord,
+        Self::Restart,
+        Self::Hint,
+        Self::Undo,
+        Self::Pause,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Reveal => tr("action.reveal"),
+            Self::Flag => tr("action.flag"),
+            Self::Chord => tr("action.chord"),
+            Self::Restart => tr("action.restart"),
+            Self::Hint => tr("action.hint"),
+            Self::Undo => tr("action.undo"),
+            Self::Pause => tr("action.pause"),
+        }
+    }
+    /// Whether the action works on the cell under the pointer or the keyboard cursor,
+    /// rather than on the whole round.
+    pub fn targets_cell(self) -> bool {
+        matches!(self, Self::Reveal | Self::Flag | Self::Chord)
+    }
+}
+
+/// Row and column step of a key that moves the keyboard cursor: arrows, WASD or hjkl.
+/// These keys, alone or with Shift to jump to the edge, cannot be bound to actions.
+pub fn movement(key: egui::Key) -> Option<(isize, isize)> {
+    use egui::Key;
+    match key {
+        Key::ArrowUp | Key::W | Key::K => Some((-1, 0)),
+        Key::ArrowDown | Key::S | Key::J => Some((1, 0)),
+        Key::ArrowLeft | Key::A | Key::H => Some((0, -1)),
+        Key::ArrowRight | Key::D | Key::L => Some((0, 1)),
+        _ => None,
+    }
+}
+
+/// Folds Ctrl on Windows and Linux, and Cmd on Mac, into `command`, so the same shortcut
+/// compares equal however it was captured.
+fn normalized(mut modifiers: egui::Modifiers) -> egui::Modifiers {
+    if modifiers.command {
+        modifiers.ctrl = false;
+        modifiers.mac_cmd = false;
+    }
+    modifiers
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Binding {
+    /// A click with a mouse button while holding exactly these modifiers.
+    Pointer {
+        button: egui::PointerButton,
+        modifiers: egui::Modifiers,
+    },
+    Key(egui::KeyboardShortcut),
+}
+
+impl Binding {
+    pub fn click(button: egui::PointerButton) -> Self {
+        Self::Pointer {
+            button,
+            modifiers: egui::Modifiers::NONE,
+        }
+    }
+    pub fn key(modifiers: egui::Modifiers, key: egui::Key) -> Self {
+        Self::Key(egui::KeyboardShortcut::new(normalized(modifiers), key))
+    }
+    /// A pointer press or key press from this frame's input, as a binding.
+    pub fn from_event(event: &egui::Event) -> Option<Self> {
+        match event {
+            egui::Event::PointerButton {
+                button,
+                pressed: true,
+                modifiers,
+                ..
+            } => Some(Self::Pointer {
+                button: *button,
+                modifiers: normalized(*modifiers),
+            }),
+            egui::Event::Key {
+                key,
+                pressed: true,
+                repeat: false,
+                modifiers,
+                ..
+            } => Some(Self::key(*modifiers, *key)),
+            _ => None,
+        }
+    }
+    pub fn label(&self, ctx: &egui::Context) -> String {
+        match self {
+            Self::Pointer { button, modifiers } => {
+                let button = match button {
+                    egui::PointerButton::Primary => tr("bindings.left_click"),
+                    egui::PointerButton::Secondary => tr("bindings.right_click"),
+                    egui::PointerButton::Middle => tr("bindings.middle_click"),
+                    egui::PointerButton::Extra1 => tr("bindings.back_button"),
+                    egui::PointerButton::Extra2 => tr("bindings.forward_button"),
+                };
+                let is_mac = ctx.os() == egui::os::OperatingSystem::Mac;
+                let modifiers = egui::ModifierNames::NAMES.format(modifiers, is_mac);
+                if modifiers.is_empty() {
+                    button.to_owned()
+                } else {
+                    format!("{modifiers}+{button}")
+                }
+            }
+            Self::Key(shortcut) => ctx.format_shortcut(shortcut),
+        }
+    }
+    /// Whether this is a cursor key, which [`movement`] keeps for itself.
+    pub fn is_movement(&self) -> bool {
+        match self {
+            Self::Pointer { .. } => false,
+            Self::Key(shortcut) => {
+                movement(shortcut.logical_key).is_some()
+                    && (shortcut.modifiers.is_none()
+                        || shortcut.modifiers == egui::Modifiers::SHIFT)
+            }
+        }
+    }
+    fn matches_pointer(&self, pressed: egui::PointerButton, held: egui::Modifiers) -> bool {
+        matches!(self, Self::Pointer { button, modifiers }
+            if *button == pressed && held.matches_exact(*modifiers))
+    }
+    fn matches_key(&self, pressed: egui::Key, held: egui::Modifiers) -> bool {
+        matches!(self, Self::Key(shortcut)
+            if shortcut.logical_key == pressed && held.matches_exact(shortcut.modifiers))
+    }
+}
+
+/// A binding that cannot work as set up.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BindingConflict {
+    /// Bound to more than one action, so only the first would ever run.
+    Shared {
+        binding: Binding,
+        actions: Vec<BoundAction>,
+    },
+    /// Bound to a key that moves the keyboard cursor.
+    Movement {
+        binding: Binding,
+        action: BoundAction,
+    },
+}
+
+impl BindingConflict {
+    pub fn describe(&self, ctx: &egui::Context) -> String {
+        match self {
+            Self::Shared { binding, actions } => {
+                let actions: Vec<&str> = actions.iter().map(|it| it.label()).collect();
+                tr_args(
+                    "bindings.shared",
+                    &[
+                        ("binding", &binding.label(ctx)),
+                        ("actions", &actions.join(tr("bindings.and"))),
+                    ],
+                )
+            }
+            Self::Movement { binding, action } => tr_args(
+                "bindings.movement",
+                &[
+                    ("binding", &binding.label(ctx)),
+                    ("action", &action.label()),
+                ],
+            ),
+        }
+    }
+}
+
+/// The bindings of every [`BoundAction`], in the order they are looked up.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct InputBindings {
+    pub by_action: BTreeMap<BoundAction, Vec<Binding>>,
+}
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        use egui::{Key, Modifiers, PointerButton};
+        let by_action = BTreeMap::from([
+            (
+                BoundAction::Reveal,
+                vec![
+                    Binding::click(PointerButton::Primary),
+                    Binding::key(Modifiers::NONE, Key::Space),
+                    Binding::key(Modifiers::NONE, Key::Enter),
+                ],
+            ),
+            (
+                BoundAction::Flag,
+                vec![
+                    Binding::click(PointerButton::Secondary),
+                    Binding::key(Modifiers::NONE, Key::F),
+                ],
+            ),
+            (
+                BoundAction::Chord,
+                vec![
+                    Binding::click(PointerButton::Middle),
+                    Binding::key(Modifiers::NONE, Key::C),
+                ],
+            ),
+            (
+                BoundAction::Restart,
+                vec![
+                    Binding::key(Modifiers::NONE, Key::F2),
+                    Binding::key(Modifiers::NONE, Key::N),
+                ],
+            ),
+            (
+                BoundAction::Hint,
+                vec![Binding::key(Modifiers::NONE, Key::I)],
+            ),
+            (
+                BoundAction::Undo,
+                vec![
+                    Binding::key(Modifiers::COMMAND, Key::Z),
+                    Binding::key(Modifiers::NONE, Key::U),
+                ],
+            ),
+            (
+                BoundAction::Pause,
+                vec![Binding::key(Modifiers::NONE, Key::P)],
+            ),
+        ]);
+        Self { by_action }
+    }
+}
+
+impl InputBindings {
+    pub fn bindings(&self, action: BoundAction) -> &[Binding] {
+        self.by_action.get(&action).map_or(&[], Vec::as_slice)
+    }
+    pub fn add(&mut self, action: BoundAction, binding: Binding) {
+        let bindings = self.by_action.entry(action).or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+    }
+    pub fn remove(&mut self, action: BoundAction, binding: &Binding) {
+        if let Some(bindings) = self.by_action.get_mut(&action) {
+            bindings.retain(|it| it != binding);
+        }
+    }
+    /// Swaps the left and right mouse buttons in every binding, for left-handed players.
+    pub fn swap_mouse_buttons(&mut self) {
+        for binding in self.by_action.values_mut().flatten() {
+            if let Binding::Pointer { button, .. } = binding {
+                *button = match *button {
+                    egui::PointerButton::Primary => egui::PointerButton::Secondary,
+                    egui::PointerButton::Secondary => egui::PointerButton::Primary,
+                    other => other,
+                };
+            }
+        }
+    }
+    /// The mouse button that reveals without modifiers, whose presses the board previews.
+    pub fn reveal_button(&self) -> Option<egui::PointerButton> {
+        self.bindings(BoundAction::Reveal)
+            .iter()
+            .find_map(|it| match it {
+                Binding::Pointer { button, modifiers } if modifiers.is_none() => Some(*button),
+                _ => None,
+            })
+    }
+    pub fn pointer_action(
+        &self,
+        button: egui::PointerButton,
+        modifiers: egui::Modifiers,
+    ) -> Option<BoundAction> {
+        BoundAction::ALL.into_iter().find(|action| {
+            self.bindings(*action)
+                .iter()
+                .any(|it| it.matches_pointer(button, modifiers))
+        })
+    }
+    pub fn key_action(&self, key: egui::Key, modifiers: egui::Modifiers) -> Option<BoundAction> {
+        BoundAction::ALL.into_iter().find(|action| {
+            self.bindings(*action)
+                .iter()
+                .any(|it| it.matches_key(key, modifiers))
+        })
+    }
+    pub fn conflicts(&self) -> Vec<BindingConflict> {
+        let mut result = vec![];
+        let mut seen: Vec<Binding> = vec![];
+        for action in BoundAction::ALL {
+            for binding in self.bindings(action) {
+                if binding.is_movement() {
+                    result.push(BindingConflict::Movement {
+                        binding: *binding,
+                        action,
+                    });
+                }
+                if seen.contains(binding) {
+                    continue;
+                }
+                seen.push(*binding);
+                let actions: Vec<BoundAction> = BoundAction::ALL
+                    .into_iter()
+                    .filter(|it| self.bindings(*it).contains(binding))
+                    .collect();
+                if actions.len() > 1 {
+                    result.push(BindingConflict::Shared {
+                        binding: *binding,
+                        actions,
+                    });
+                }
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::{Key, Modifiers, PointerButton};
+
+    use super::{Binding, BindingConflict, BoundAction, InputBindings};
+
+    fn key_event(key: Key, modifiers: Modifiers) -> egui::Event {
+        egui::Event::Key {
+            key,
+            physical_key: None,
+            pressed: true,
+            repeat: false,
+            modifiers,
+        }
+    }
+
+    #[test]
+    fn defaults_have_no_conflicts() {
+        assert_eq!(InputBindings::default().conflicts(), [], "clean defaults");
+    }
+
+    #[test]
+    fn shared_binding_is_reported_once_naming_every_action() {
+        let mut bindings = InputBindings::default();
+        let binding = Binding::key(Modifiers::NONE, Key::F);
+        bindings.add(BoundAction::Hint, binding);
+        let conflicts = bindings.conflicts();
+        assert_eq!(
+            conflicts,
+            [BindingConflict::Shared {
+                binding,
+                actions: vec![BoundAction::Flag, BoundAction::Hint],
+            }],
+            "one conflict for both actions"
+        );
+        let description = conflicts
+            .first()
+            .expect("one conflict")
+            .describe(&egui::Context::default());
+        assert!(
+            description.contains("Flag and Hint"),
+            "names both actions: {description}"
+        );
+    }
+
+    #[test]
+    fn movement_keys_cannot_be_bound() {
+        let mut bindings = InputBindings::default();
+        bindings.add(
+            BoundAction::Hint,
+            Binding::key(Modifiers::NONE, Key::ArrowUp),
+        );
+        bindings.add(BoundAction::Pause, Binding::key(Modifiers::SHIFT, Key::H));
+        bindings.add(
+            BoundAction::Restart,
+            Binding::key(Modifiers::COMMAND, Key::ArrowDown),
+        );
+        assert_eq!(
+            bindings.conflicts(),
+            [
+                BindingConflict::Movement {
+                    binding: Binding::key(Modifiers::NONE, Key::ArrowUp),
+                    action: BoundAction::Hint,
+                },
+                BindingConflict::Movement {
+                    binding: Binding::key(Modifiers::SHIFT, Key::H),
+                    action: BoundAction::Pause,
+                },
+            ],
+            "plain and shifted movement keys, but not other modifiers"
+        );
+    }
+
+    #[test]
+    fn command_matches_ctrl_and_cmd_however_it_was_captured() {
+        let bindings = InputBindings::default();
+        let ctrl = Modifiers::CTRL | Modifiers::COMMAND;
+        let cmd = Modifiers::MAC_CMD | Modifiers::COMMAND;
+        for (held, os) in [(ctrl, "Ctrl"), (cmd, "Cmd")] {
+            assert_eq!(
+                Binding::from_event(&key_event(Key::Z, held)),
+                Some(Binding::key(Modifiers::COMMAND, Key::Z)),
+                "{os}+Z captured"
+            );
+            assert_eq!(
+                bindings.key_action(Key::Z, held),
+                Some(BoundAction::Undo),
+                "{os}+Z pressed"
+            );
+        }
+        assert_eq!(
+            bindings.key_action(Key::Z, Modifiers::NONE),
+            None,
+            "Z alone"
+        );
+    }
+
+    #[test]
+    fn swapping_the_mouse_buttons_twice_restores_the_defaults() {
+        let mut bindings = InputBindings::default();
+        bindings.swap_mouse_buttons();
+        assert_eq!(
+            bindings.reveal_button(),
+            Some(PointerButton::Secondary),
+            "reveal on the right button"
+        );
+        assert_eq!(
+            bindings.pointer_action(PointerButton::Primary, Modifiers::NONE),
+            Some(BoundAction::Flag),
+            "flag on the left button"
+        );
+        bindings.swap_mouse_buttons();
+        assert_eq!(bindings, InputBindings::default(), "back to the defaults");
+    }
+}
@@ -0,0 +1,284 @@
+//! Plain-text board layouts, for tests, bug reports and puzzle packs.
+//!
+//! A board is one line per row and one character per cell:
+//!
+//! | char      | cell                                                    |
+//! |-----------|---------------------------------------------------------|
+//! | `.`       | covered safe cell                                       |
+//! | `*`       | covered mine                                            |
+//! | `f`       | flagged safe cell (a wrong flag)                        |
+//! | `F`       | flagged mine                                            |
+//! | `0` – `8` | revealed safe cell; the digit must match its neighbours |
+//! | `X`       | revealed mine                                           |
+//!
+//! Lines starting with `#` are comments. Blank lines and trailing whitespace are ignored,
+//! and every row must have the same length:
+//!
+//! ```text
+//! # beginner, lost on the top-left mine
+//! X1......
+//! 11..*...
+//! ```
+//!
+//! [`write`] without state only emits `.` and `*`; [`read`] accepts both forms.
+
+use crate::config::{BoardConfig, ConfigError};
+use crate::data::{CellPos, CellRenderState, GameBoardData};
+use crate::i18n::{tr, tr_args};
+
+/// Where and why a board failed to parse. Lines and columns are 1-based.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BoardFormatError {
+    pub line: usize,
+    pub column: usize,
+    pub problem: BoardFormatProblem,
+}
+
+impl std::fmt::Display for BoardFormatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&tr_args(
+            "board_format.error",
+            &[
+                ("line", &self.line),
+                ("column", &self.column),
+                ("problem", &self.problem),
+            ],
+        ))
+    }
+}
+
+impl std::error::Error for BoardFormatError {}
+
+/// What is wrong at the place a [`BoardFormatError`] points to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BoardFormatProblem {
+    /// A row whose length differs from the first row.
+    RowLength {
+        expected: usize,
+        found: usize,
+    },
+    NoRows,
+    UnknownCell(char),
+    /// The layout parsed but is not a playable board.
+    InvalidBoard(ConfigError),
+    /// A revealed digit that does not match the mines around it.
+    WrongDigit {
+        shown: char,
+        mines: usize,
+    },
+}
+
+impl std::fmt::Display for BoardFormatProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::RowLength { expected, found } => f.write_str(&tr_args(
+                "board_format.row_length",
+                &[("expected", expected), ("found", found)],
+            )),
+            Self::NoRows => f.write_str(tr("board_format.no_rows")),
+            Self::UnknownCell(symbol) => {
+                f.write_str(&tr_args("board_format.unknown_cell", &[("symbol", symbol)]))
+            }
+            Self::InvalidBoard(err) => err.fmt(f),
+            Self::WrongDigit { shown, mines } => f.write_str(&tr_args(
+                "board_format.wrong_digit",
+                &[("shown", shown), ("mines", mines)],
+            )),
+        }
+    }
+}
+
+/// Serializes a board. With `include_state`, reveals and flags are written too.
+pub fn write(board: &GameBoardData, include_state: bool) -> String {
+    let mut text = String::new();
+    for row in &board.cells {
+        for cell in row {
+            let revealed = include_state && cell.render_state == CellRenderState::Revealed;
+            let flagged = include_state && cell.is_flagged;
+            text.push(match (cell.is_mine, revealed, flagged) {
+                (true, true, _) => 'X',
+                (true, false, true) => 'F',
+                (true, false, false) => '*',
+                (false, true, _) => digit(cell.nearby_mines),
+                (false, false, true) => 'f',
+                (false, false, false) => '.',
+            });
+        }
+        text.push('\n');
+    }
+    text
+}
+
+fn digit(value: usize) -> char {
+    u32::try_from(value)
+        .ok()
+        .and_then(|it| char::from_digit(it, 10))
+        .unwrap_or('?')
+}
+
+/// Parses a board written by [`write`] or by hand.
+///
+/// # Errors
+///
+/// Fails on an unknown character, rows of different lengths, an empty or unplayable board,
+/// or a revealed digit that disagrees with the mines around it.
+pub fn read(text: &str) -> Result<GameBoardData, BoardFormatError> {
+    let mut rows: Vec<(usize, Vec<char>)> = vec![];
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let cells: Vec<char> = line.chars().collect();
+        if let Some((_, first)) = rows.first() {
+            if cells.len() != first.len() {
+                return Err(BoardFormatError {
+                    line: index + 1,
+                    column: cells.len().min(first.len()) + 1,
+                    problem: BoardFormatProblem::RowLength {
+                        expected: first.len(),
+                        found: cells.len(),
+                    },
+                });
+            }
+        }
+        rows.push((index + 1, cells));
+    }
+    let Some((_, first)) = rows.first() else {
+        return Err(BoardFormatError {
+            line: text.lines().count().max(1),
+            column: 1,
+            problem: BoardFormatProblem::NoRows,
+        });
+    };
+
+    let width = first.len();
+    let mut mines = vec![];
+    for (row, (line, cells)) in rows.iter().enumerate() {
+        for (column, symbol) in cells.iter().enumerate() {
+            match symbol {
+                '*' | 'F' | 'X' => mines.push(CellPos::new(row, column)),
+                '.' | 'f' | '0'..='8' => {}
+                _ => {
+                    return Err(BoardFormatError {
+                        line: *line,
+                        column: column + 1,
+                        problem: BoardFormatProblem::UnknownCell(*symbol),
+                    });
+                }
+            }
+        }
+    }
+
+    if let Err(err) = BoardConfig::new(width, rows.len(), mines.len()) {
+        return Err(BoardFormatError {
+            line: rows.first().map_or(1, |(line, _)| *line),
+            column: 1,
+            problem: BoardFormatProblem::InvalidBoard(err),
+        });
+    }
+
+    let mut board = crate::game_logic::board_from_mines(width, rows.len(), &mines);
+    for (row, (line, cells)) in rows.iter().enumerate() {
+        for (column, symbol) in cells.iter().enumerate() {
+            let Some(cell) = board.get_cell_mut(&CellPos::new(row, column)) else {
+                continue;
+            };
+            match symbol {
+                'F' | 'f' => cell.is_flagged = true,
+                'X' => cell.render_state = CellRenderState::Revealed,
+                '0'..='8' => {
+                    if digit(cell.nearby_mines) != *symbol {
+                        return Err(BoardFormatError {
+                            line: *line,
+                            column: column + 1,
+                            problem: BoardFormatProblem::WrongDigit {
+                                shown: *symbol,
+                                mines: cell.nearby_mines,
+                            },
+                        });
+                    }
+                    cell.render_state = CellRenderState::Revealed;
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok(board)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BoardFormatError, BoardFormatProblem, read, write};
+
+    const LOST: &str = "X1..\n11..\n.f.F\n";
+
+    fn error(text: &str) -> BoardFormatError {
+        read(text).expect_err("board should not parse")
+    }
+
+    #[test]
+    fn round_trips_with_state() {
+        let board = read(LOST).expect("valid board");
+        assert_eq!(write(&board, true), LOST, "written back as read");
+        assert_eq!(write(&board, false), "*...\n....\n...*\n", "layout only");
+        let layout = read(&write(&board, false)).expect("valid layout");
+        assert_eq!(
+            layout.get_mine_positions(),
+            board.get_mine_positions(),
+            "same mines"
+        );
+    }
+
+    #[test]
+    fn skips_comments_and_blank_lines() {
+        let board = read("# lost\n\nX1..  \n11..\n\n.f.F\n").expect("valid board");
+        assert_eq!(write(&board, true), LOST, "only the rows are kept");
+    }
+
+    #[test]
+    fn reports_unknown_cells_where_they_are() {
+        let err = error("# comment\n....\n..?.\n*...\n");
+        assert_eq!((err.line, err.column), (3, 3), "{err}");
+        assert_eq!(err.problem, BoardFormatProblem::UnknownCell('?'), "{err}");
+    }
+
+    #[test]
+    fn reports_rows_of_the_wrong_length() {
+        let err = error("....\n.*.\n....\n");
+        assert_eq!((err.line, err.column), (2, 4), "{err}");
+        assert_eq!(
+            err.problem,
+            BoardFormatProblem::RowLength {
+                expected: 4,
+                found: 3
+            },
+            "{err}"
+        );
+        let err = error("....\n\n.*...\n");
+        assert_eq!((err.line, err.column), (3, 5), "{err}");
+    }
+
+    #[test]
+    fn reports_digits_that_disagree_with_the_mines() {
+        let err = error("*...\n.2..\n....\n");
+        assert_eq!((err.line, err.column), (2, 2), "{err}");
+        assert_eq!(
+            err.problem,
+            BoardFormatProblem::WrongDigit {
+                shown: '2',
+                mines: 1
+            },
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn rejects_empty_and_unplayable_boards() {
+        let err = error("# nothing here\n\n");
+        assert_eq!((err.line, err.column), (2, 1), "{err}");
+        assert_eq!(err.problem, BoardFormatProblem::NoRows, "{err}");
+        let err = error("\n**\n**\n");
+        assert_eq!((err.line, err.column), (2, 1), "{err}");
+    }
+}
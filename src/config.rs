@@ -0,0 +1,229 @@
+//! Board dimensions and mine counts, checked once so the rest of the game can trust them.
+
+use crate::i18n::tr_args;
+
+/// Shortest side a board may have.
+pub const MIN_SIDE: usize = 2;
+/// Longest side a board may have; the board only draws the cells in view, so even the
+/// largest boards stay smooth when zoomed in, and saves pack the board into bitmaps.
+pub const MAX_SIDE: usize = 500;
+/// Cells kept clear around the first reveal for a safe opening.
+pub const OPENING_CELLS: usize = 9;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConfigError {
+    TooSmall {
+        width: usize,
+        height: usize,
+    },
+    TooLarge {
+        width: usize,
+        height: usize,
+    },
+    /// Every cell would be a mine, leaving nothing to reveal.
+    TooManyMines {
+        mines: usize,
+        max: usize,
+    },
+    /// The mines leave no room to clear the first reveal and its neighbours.
+    NoRoomForOpening {
+        mines: usize,
+        max: usize,
+    },
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self {
+            Self::TooSmall { width, height } => tr_args(
+                "config.too_small",
+                &[("width", width), ("height", height), ("min", &MIN_SIDE)],
+            ),
+            Self::TooLarge { width, height } => tr_args(
+                "config.too_large",
+                &[("width", width), ("height", height), ("max", &MAX_SIDE)],
+            ),
+            Self::TooManyMines { mines, max } => {
+                tr_args("config.too_many_mines", &[("mines", mines), ("max", max)])
+            }
+            Self::NoRoomForOpening { mines, max } => tr_args(
+                "config.no_room_for_opening",
+                &[("mines", mines), ("max", max)],
+            ),
+        };
+        f.write_str(&message)
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// A board size and mine count that can be played.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(try_from = "BoardConfigFields")]
+pub struct BoardConfig {
+    width: usize,
+    height: usize,
+    mines: usize,
+}
+
+/// The unchecked form that [`BoardConfig`] is deserialized through.
+#[derive(serde::Deserialize)]
+struct BoardConfigFields {
+    width: usize,
+    height: usize,
+    mines: usize,
+}
+
+impl TryFrom<BoardConfigFields> for BoardConfig {
+    type Error = ConfigError;
+
+    fn try_from(fields: BoardConfigFields) -> Result<Self, Self::Error> {
+        Self::new(fields.width, fields.height, fields.mines)
+    }
+}
+
+impl Default for BoardConfig {
+    fn default() -> Self {
+        Self {
+            width: crate::constants::DEFAULT_BOARD_SIZE,
+            height: crate::constants::DEFAULT_BOARD_SIZE,
+            mines: crate::constants::DEFAULT_MINE_AMOUNT,
+        }
+    }
+}
+
+impl BoardConfig {
+    /// # Errors
+    ///
+    /// Fails when a side is shorter than [`MIN_SIDE`] or longer than [`MAX_SIDE`], or when
+    /// the mines would fill every cell.
+    pub fn new(width: usize, height: usize, mines: usize) -> Result<Self, ConfigError> {
+        if width < MIN_SIDE || height < MIN_SIDE {
+            return Err(ConfigError::TooSmall { width, height });
+        }
+        if width > MAX_SIDE || height > MAX_SIDE {
+            return Err(ConfigError::TooLarge { width, height });
+        }
+        let max = width * height - 1;
+        if mines > max {
+            return Err(ConfigError::TooManyMines { mines, max });
+        }
+        Ok(Self {
+            width,
+            height,
+            mines,
+        })
+    }
+
+    /// Checks that a first reveal anywhere can be cleared along with its neighbours.
+    ///
+    /// # Errors
+    ///
+    /// Fails when more than `width * height - OPENING_CELLS` mines are placed.
+    pub fn require_safe_opening(self) -> Result<Self, ConfigError> {
+        let max = self.cell_count().saturating_sub(OPENING_CELLS);
+        if self.mines > max {
+            return Err(ConfigError::NoRoomForOpening {
+                mines: self.mines,
+                max,
+            });
+        }
+        Ok(self)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+    pub fn height(&self) -> usize {
+        self.height
+    }
+    pub fn mines(&self) -> usize {
+        self.mines
+    }
+    pub fn cell_count(&self) -> usize {
+        self.width * self.height
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BoardConfig, ConfigError, MAX_SIDE, MIN_SIDE, OPENING_CELLS};
+
+    #[test]
+    fn sides_must_be_within_bounds() {
+        assert_eq!(
+            BoardConfig::new(MIN_SIDE - 1, 9, 1),
+            Err(ConfigError::TooSmall {
+                width: MIN_SIDE - 1,
+                height: 9
+            }),
+            "narrow board"
+        );
+        assert_eq!(
+            BoardConfig::new(9, MIN_SIDE - 1, 1),
+            Err(ConfigError::TooSmall {
+                width: 9,
+                height: MIN_SIDE - 1
+            }),
+            "short board"
+        );
+        assert_eq!(
+            BoardConfig::new(MAX_SIDE + 1, 9, 1),
+            Err(ConfigError::TooLarge {
+                width: MAX_SIDE + 1,
+                height: 9
+            }),
+            "wide board"
+        );
+        assert!(
+            BoardConfig::new(MIN_SIDE, MAX_SIDE, 1).is_ok(),
+            "both bounds are inclusive"
+        );
+    }
+
+    #[test]
+    fn mines_must_leave_a_cell_to_reveal() {
+        assert_eq!(
+            BoardConfig::new(3, 3, 9),
+            Err(ConfigError::TooManyMines { mines: 9, max: 8 }),
+            "every cell a mine"
+        );
+        let config = BoardConfig::new(3, 3, 8).expect("one safe cell is enough");
+        assert_eq!(config.cell_count(), 9, "3×3");
+    }
+
+    #[test]
+    fn safe_opening_needs_room_for_the_first_reveal_and_its_neighbours() {
+        let max = 9 * 9 - OPENING_CELLS;
+        let config = BoardConfig::new(9, 9, max).expect("valid board");
+        assert_eq!(
+            config.require_safe_opening(),
+            Ok(config),
+            "exactly enough room"
+        );
+        let config = BoardConfig::new(9, 9, max + 1).expect("valid board");
+        assert_eq!(
+            config.require_safe_opening(),
+            Err(ConfigError::NoRoomForOpening {
+                mines: max + 1,
+                max
+            }),
+            "one mine too many"
+        );
+    }
+
+    #[test]
+    fn safe_opening_on_boards_smaller_than_the_opening() {
+        let config = BoardConfig::new(2, 2, 1).expect("valid board");
+        assert_eq!(
+            config.require_safe_opening(),
+            Err(ConfigError::NoRoomForOpening { mines: 1, max: 0 }),
+            "no mines fit around a 2×2 opening"
+        );
+        let config = BoardConfig::new(2, 2, 0).expect("valid board");
+        assert!(
+            config.require_safe_opening().is_ok(),
+            "a board without mines"
+        );
+    }
+}
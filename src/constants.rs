@@ -1,3 +1,5 @@
+/// Names the native window and the folder the app keeps its data in.
+pub static APP_ID: &str = "MineSweeper";
 pub static WINDOW_RATIO: f32 = 0.8;
 pub static DEFAULT_BOARD_SIZE: usize = 16;
 pub static DEFAULT_MINE_AMOUNT: usize = 40;
@@ -1,21 +1,88 @@
-use chrono::{DateTime, TimeZone as _};
+use std::sync::atomic::{AtomicU64, Ordering};
 
-use crate::constants::{DEFAULT_BOARD_SIZE, DEFAULT_MINE_AMOUNT};
+use chrono::DateTime;
 
-#[derive(serde::Deserialize, serde::Serialize, Clone, Debug)]
+use crate::config::{BoardConfig, ConfigError};
+use crate::replay::Replay;
+
+/// Storage key of the [`GameSettings`].
+pub const SETTINGS_KEY: &str = "settings";
+/// Storage key of the [`TouchMode`].
+pub const TOUCH_MODE_KEY: &str = "touch_mode";
+
+/// The board and options new rounds start with.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(try_from = "GameSettingsFields")]
 pub struct GameSettings {
-    pub board_size: i16,
-    pub total_mines: i16,
+    board: BoardConfig,
+    /// Moves mines away from the first reveal so every round starts on an opening.
+    safe_first_click: bool,
+    /// Chords with one click on a revealed number instead of two.
+    single_click_chord: bool,
 }
 
-impl Default for GameSettings {
-    fn default() -> Self {
-        Self {
-            board_size: 16,
-            total_mines: 40,
-        }
+/// The unchecked form that [`GameSettings`] is deserialized through.
+#[derive(serde::Deserialize, Default)]
+#[serde(default)]
+struct GameSettingsFields {
+    board: BoardConfig,
+    safe_first_click: bool,
+    single_click_chord: bool,
+}
+
+impl TryFrom<GameSettingsFields> for GameSettings {
+    type Error = ConfigError;
+
+    fn try_from(fields: GameSettingsFields) -> Result<Self, Self::Error> {
+        Self::new(
+            fields.board,
+            fields.safe_first_click,
+            fields.single_click_chord,
+        )
+    }
+}
+
+impl GameSettings {
+    /// # Errors
+    ///
+    /// Fails when a safe first click is asked for on a board without room for one.
+    pub fn new(
+        board: BoardConfig,
+        safe_first_click: bool,
+        single_click_chord: bool,
+    ) -> Result<Self, ConfigError> {
+        let board = if safe_first_click {
+            board.require_safe_opening()?
+        } else {
+            board
+        };
+        Ok(Self {
+            board,
+            safe_first_click,
+            single_click_chord,
+        })
+    }
+    pub fn board(&self) -> BoardConfig {
+        self.board
+    }
+    pub fn safe_first_click(&self) -> bool {
+        self.safe_first_click
+    }
+    pub fn single_click_chord(&self) -> bool {
+        self.single_click_chord
+    }
+    pub fn difficulty(&self) -> crate::statistics::Difficulty {
+        crate::statistics::Difficulty::classify(
+            self.board.width(),
+            self.board.height(),
+            self.board.mines(),
+        )
+    }
+    pub fn new_round(&self) -> RoundData {
+        RoundData::new(self.board)
     }
 }
+
 #[derive(serde::Deserialize, serde::Serialize, Clone, Debug)]
 pub struct LayoutState {
     pub scene_panel_height: f32,
@@ -23,10 +90,20 @@ pub struct LayoutState {
     pub global_x_padding: f32,
 }
 
+/// What a tap on a covered cell does on a touch screen, where there is no right button.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TouchMode {
+    #[default]
+    Reveal,
+    Flag,
+}
+
 #[derive(serde::Deserialize, serde::Serialize, Clone, Debug)]
 pub struct GlobalState {
     pub current_scene: crate::scenes::SceneType,
     pub game_settings: GameSettings,
+    #[serde(default)]
+    pub touch_mode: TouchMode,
     // pub round_state: RoundState,
     pub window_size: (f32, f32),
     pub content_size: (f32, f32),
@@ -34,7 +111,9 @@ pub struct GlobalState {
 }
 
 impl GlobalState {
-    pub fn change_scene() {}
+    pub fn change_scene(&mut self, scene: crate::scenes::SceneType) {
+        self.current_scene = scene;
+    }
 }
 
 impl Default for GlobalState {
@@ -42,6 +121,7 @@ impl Default for GlobalState {
         Self {
             current_scene: crate::scenes::SceneType::Game,
             game_settings: Default::default(),
+            touch_mode: TouchMode::default(),
             // round_state: RoundState::default(),
             window_size: (400.0, 500.0),
             content_size: (400.0, 500.0),
@@ -80,156 +160,199 @@ impl Default for CellData {
         }
     }
 }
+/// Source of [`GameBoardData::revision`], shared by every board so no two boards that
+/// differ can have the same revision.
+static REVISIONS: AtomicU64 = AtomicU64::new(0);
+
+pub(crate) fn next_revision() -> u64 {
+    REVISIONS.fetch_add(1, Ordering::Relaxed) + 1
+}
+
 #[derive(serde::Deserialize, serde::Serialize, Clone, Debug)]
 pub struct GameBoardData {
     #[serde(skip)]
     pub last_click: Option<(CellPos, DateTime<chrono::Utc>)>,
     pub cells: Vec<Vec<CellData>>,
+    #[serde(skip, default = "next_revision")]
+    pub(crate) revision: u64,
 }
 
 impl GameBoardData {
+    /// Changes whenever a cell may have, so what is drawn from the board can be kept until
+    /// then.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+    /// Number of columns.
+    pub fn width(&self) -> usize {
+        self.cells.first().map_or(0, Vec::len)
+    }
+    /// Number of rows.
+    pub fn height(&self) -> usize {
+        self.cells.len()
+    }
+    #[expect(clippy::print_stdout, reason = "debug helper")]
     pub fn show_mine_location(&self) {
-        let mut text = String::new();
-        let board_size = self.cells.len();
-        for j in 0..board_size {
-            let mut tmp_string = String::new();
-            for k in 0..board_size {
-                if (self.cells[j][k]).is_mine {
-                    tmp_string.push_str(" X");
-                } else {
-                    tmp_string.push_str(" O");
-                }
-            }
-            tmp_string.push('\n');
-            text += tmp_string.as_str();
-            tmp_string.clear();
-        }
-        println!("{text}");
+        println!("{}", crate::board_format::write(self, false));
     }
+    #[expect(clippy::print_stdout, reason = "debug helper")]
     pub fn show_game_board(&self) {
-        let mut text = String::new();
-        let board_size = self.cells.len();
-        for j in 0..board_size {
-            let mut tmp_string = String::new();
-            for k in 0..board_size {
-                if (self.cells[j][k]).is_mine {
-                    tmp_string.push_str(" X");
-                } else {
-                    tmp_string.push_str(format!(" {}", self.cells[j][k].nearby_mines).as_str());
-                }
-            }
-            tmp_string.push('\n');
-            text += tmp_string.as_str();
-            tmp_string.clear();
-        }
-        println!("{text}");
+        println!("{}", crate::board_format::write(self, true));
     }
     pub fn get_flag_count(&self) -> usize {
-        let result = self
-            .cells
+        self.cells
             .iter()
-            .map(|it| {
-                it.iter()
-                    .map(|it| i32::from(it.is_flagged))
-                    .reduce(|a, b| a + b)
-                    .unwrap_or(0)
-            })
-            .reduce(|a, b| a + b)
-            .unwrap_or(0);
-        result as usize
+            .map(|it| it.iter().filter(|it| it.is_flagged).count())
+            .sum()
     }
     pub fn get_remain_cell_count(&self) -> usize {
-        let result = self
-            .cells
+        self.cells
             .iter()
             .map(|it| {
                 it.iter()
-                    .map(|it| i32::from(it.render_state == CellRenderState::Covered))
-                    .reduce(|a, b| a + b)
-                    .unwrap_or(0)
+                    .filter(|it| it.render_state == CellRenderState::Covered)
+                    .count()
             })
-            .reduce(|a, b| a + b)
-            .unwrap_or(0);
-        result as usize
-    }
-    pub fn get_cell(&self, pos: &CellPos) -> CellData {
-        self.cells[pos.x][pos.y].clone()
-    }
-    pub fn update_cells(&mut self, data: Vec<CellData>) {
-        let board_size = self.cells.len();
-        for cell in &data {
-            if cell.position.x < board_size && cell.position.y < board_size {
-                self.cells[cell.position.x][cell.position.y] = cell.clone();
-            }
-        }
+            .sum()
     }
-    pub fn get_surround_cells(&self, pos: &CellPos) -> Vec<CellData> {
-        let max_index = self.cells.len() - 1;
-        let mut result: Vec<CellData> = vec![];
-
-        if pos.x > 0 {
-            result.push(self.cells[pos.x - 1][pos.y].clone());
-            if pos.y > 0 {
-                result.push(self.cells[pos.x - 1][pos.y - 1].clone());
-            }
-            if pos.y < max_index {
-                result.push(self.cells[pos.x - 1][pos.y + 1].clone());
+    /// Positions of every mine on the board, row by row.
+    pub fn get_mine_positions(&self) -> Vec<CellPos> {
+        self.cells
+            .iter()
+            .flatten()
+            .filter(|it| it.is_mine)
+            .map(|it| it.position.clone())
+            .collect()
+    }
+    pub fn get_cell(&self, pos: &CellPos) -> Option<&CellData> {
+        self.cells.get(pos.x)?.get(pos.y)
+    }
+    pub fn get_cell_mut(&mut self, pos: &CellPos) -> Option<&mut CellData> {
+        self.revision = next_revision();
+        self.cells.get_mut(pos.x)?.get_mut(pos.y)
+    }
+    pub fn update_cells(&mut self, data: &[CellData]) {
+        for cell in data {
+            if let Some(target) = self.get_cell_mut(&cell.position) {
+                *target = cell.clone();
             }
         }
-        if pos.x < max_index {
-            result.push(self.cells[pos.x + 1][pos.y].clone());
-            if pos.y > 0 {
-                result.push(self.cells[pos.x + 1][pos.y - 1].clone());
-            }
-            if pos.y < max_index {
-                result.push(self.cells[pos.x + 1][pos.y + 1].clone());
+    }
+    /// Positions of the (up to eight) cells touching `pos` that lie on the board.
+    pub fn get_surround_positions(&self, pos: &CellPos) -> Vec<CellPos> {
+        let mut result = vec![];
+        for x in pos.x.saturating_sub(1)..=pos.x + 1 {
+            for y in pos.y.saturating_sub(1)..=pos.y + 1 {
+                let it = CellPos::new(x, y);
+                if it != *pos && self.get_cell(&it).is_some() {
+                    result.push(it);
+                }
             }
         }
-        if pos.y > 0 {
-            result.push(self.cells[pos.x][pos.y - 1].clone());
-        }
-        if pos.y < max_index {
-            result.push(self.cells[pos.x][pos.y + 1].clone());
-        }
-
         result
     }
+    pub fn get_surround_cells(&self, pos: &CellPos) -> Vec<CellData> {
+        self.get_surround_positions(pos)
+            .iter()
+            .filter_map(|it| self.get_cell(it).cloned())
+            .collect()
+    }
 }
 
 impl Default for GameBoardData {
     fn default() -> Self {
-        crate::game_logic::generate_new_board(DEFAULT_BOARD_SIZE, DEFAULT_MINE_AMOUNT)
+        crate::game_logic::generate_new_board(BoardConfig::default())
     }
 }
 
 #[derive(serde::Deserialize, serde::Serialize, Clone, Debug)]
 pub struct RoundData {
-    pub start_time: u32,
+    /// Unix timestamp in milliseconds of the first reveal.
+    pub start_time: i64,
     #[serde(skip)]
     pub time_passed: u32,
-    pub board_size: usize,
+    pub board_width: usize,
+    pub board_height: usize,
     pub total_mine: usize,
-    pub flags_placed: i16,
     pub board_data: GameBoardData,
     pub round_state_type: RoundState,
-    pub mines_remaining: usize,
+    /// Mines minus flags; negative when the player has placed too many flags.
+    pub mines_remaining: i32,
+    pub replay: Replay,
+    /// Safe cells the last action applied revealed, for the board to animate them opening.
+    #[serde(skip)]
+    pub last_revealed: Vec<CellPos>,
+    /// Whether the ended round has been counted in the player statistics, or is set up
+    /// front for a round that must not be.
+    #[serde(default)]
+    pub recorded: bool,
+    /// Set once the player got help, which keeps the round off the leaderboard.
+    #[serde(default)]
+    pub assisted: bool,
+    /// The mines were laid out before the round, as on a board link or a loaded board, so
+    /// a safe first click must not move them.
+    #[serde(default)]
+    pub fixed_layout: bool,
+    /// The save slot the round was loaded from, whose copy is marked recorded along with
+    /// this round so it cannot be finished and counted again. Only saves from version 2
+    /// keep it.
+    #[serde(skip)]
+    pub slot: Option<String>,
+    /// When the round was paused, as a Unix timestamp in milliseconds. Saves store the
+    /// elapsed time instead, so a loaded round is never paused.
+    #[serde(skip)]
+    pub paused_at: Option<i64>,
 }
 
 impl RoundData {
     pub fn update_round_state(&mut self) {
-        self.mines_remaining = self.total_mine - self.board_data.get_flag_count();
-        match &mut self.round_state_type {
-            RoundState::NotStarted => {}
-            RoundState::Playing => {
-                self.time_passed = chrono::Utc::now().timestamp() as u32 - self.start_time;
-                if self.mines_remaining <= 0 {
-                    self.round_state_type = RoundState::Ended(RoundEndingType::Victory);
-                }
+        self.mines_remaining = self.total_mine as i32 - self.board_data.get_flag_count() as i32;
+        if self.round_state_type == RoundState::Playing {
+            self.time_passed = self.elapsed_ms() / 1000;
+        }
+    }
+    /// Milliseconds since the first reveal, not counting pauses, or zero before the round
+    /// has started.
+    pub fn elapsed_ms(&self) -> u32 {
+        match self.round_state_type {
+            RoundState::NotStarted => 0,
+            RoundState::Playing | RoundState::Ended(_) => {
+                let now = self
+                    .paused_at
+                    .unwrap_or_else(|| chrono::Utc::now().timestamp_millis());
+                u32::try_from(now - self.start_time).unwrap_or(0)
             }
-            RoundState::Ended(round_ending_type) => {}
         }
     }
+    pub fn is_paused(&self) -> bool {
+        self.paused_at.is_some()
+    }
+    /// Stops the clock of a round in play, or starts it again where it stopped.
+    pub fn toggle_pause(&mut self) {
+        let now = chrono::Utc::now().timestamp_millis();
+        match self.paused_at.take() {
+            Some(paused_at) => self.start_time += now - paused_at,
+            None if self.round_state_type == RoundState::Playing => self.paused_at = Some(now),
+            None => {}
+        }
+    }
+    /// Share of safe cells revealed, from 0 to 1.
+    pub fn progress(&self) -> f32 {
+        let safe_cells = (self.board_width * self.board_height).saturating_sub(self.total_mine);
+        if safe_cells == 0 {
+            return 1.0;
+        }
+        let covered_safe_cells = self
+            .board_data
+            .cells
+            .iter()
+            .flatten()
+            .filter(|it| !it.is_mine && it.render_state == CellRenderState::Covered)
+            .count();
+        1.0 - covered_safe_cells as f32 / safe_cells as f32
+    }
     pub fn solve_for_me(&mut self) {
+        self.assisted = true;
         for ele in &mut self.board_data.cells {
             for ele in ele {
                 ele.is_flagged = ele.is_mine;
@@ -252,30 +375,79 @@ pub enum RoundState {
 
 impl Default for RoundData {
     fn default() -> Self {
-        Self {
-            start_time: Default::default(),
-            board_size: DEFAULT_BOARD_SIZE,
-            total_mine: DEFAULT_MINE_AMOUNT,
-            time_passed: 0,
-            flags_placed: 0,
-            board_data: GameBoardData::default(),
-            round_state_type: RoundState::NotStarted,
-            mines_remaining: DEFAULT_MINE_AMOUNT,
-        }
+        Self::new(BoardConfig::default())
     }
 }
 
 impl RoundData {
-    pub fn new(board_size: usize, total_mine: usize) -> Self {
+    pub fn new(config: BoardConfig) -> Self {
+        Self::from_board(
+            config.mines(),
+            crate::game_logic::generate_new_board(config),
+        )
+    }
+    /// Continues a round from a board that may already have reveals and flags, such as
+    /// one loaded from the [`crate::board_format`].
+    ///
+    /// The replay keeps those reveals and flags, so undo and playback start from them. A
+    /// round that starts part played is never recorded, as its scores would be meaningless.
+    pub fn from_board_state(board_data: GameBoardData) -> Self {
+        let total_mine = board_data.get_mine_positions().len();
+        let revealed_mine = board_data
+            .cells
+            .iter()
+            .flatten()
+            .find(|it| it.is_mine && it.render_state == CellRenderState::Revealed)
+            .map(|it| it.position.clone());
+        let remain_cells = board_data.get_remain_cell_count();
+        let positions = |keep: fn(&CellData) -> bool| -> Vec<CellPos> {
+            board_data
+                .cells
+                .iter()
+                .flatten()
+                .filter(|it| keep(it))
+                .map(|it| it.position.clone())
+                .collect()
+        };
+        let revealed = positions(|it| it.render_state == CellRenderState::Revealed);
+        let flagged = positions(|it| it.is_flagged);
+        let mut round = Self::from_board(total_mine, board_data);
+        round.fixed_layout = true;
+        round.recorded = !revealed.is_empty() || !flagged.is_empty();
+        round.replay.revealed = revealed;
+        round.replay.flagged = flagged;
+        round.round_state_type = match revealed_mine {
+            Some(pos) => RoundState::Ended(RoundEndingType::ClickedMine(pos)),
+            None if remain_cells == total_mine => RoundState::Ended(RoundEndingType::Victory),
+            None if remain_cells < round.board_width * round.board_height => RoundState::Playing,
+            None => RoundState::NotStarted,
+        };
+        round.start_time = chrono::Utc::now().timestamp_millis();
+        round.update_round_state();
+        round
+    }
+    /// Starts a fresh round on an already generated board.
+    pub fn from_board(total_mine: usize, board_data: GameBoardData) -> Self {
         Self {
-            board_size,
+            board_width: board_data.width(),
+            board_height: board_data.height(),
             total_mine,
-            flags_placed: 0,
-            board_data: crate::game_logic::generate_new_board(board_size, total_mine),
+            replay: Replay::new(
+                board_data.width(),
+                board_data.height(),
+                board_data.get_mine_positions(),
+            ),
+            board_data,
             start_time: 0,
             time_passed: 0,
             round_state_type: RoundState::NotStarted,
-            mines_remaining: total_mine,
+            mines_remaining: total_mine as i32,
+            recorded: false,
+            assisted: false,
+            fixed_layout: false,
+            slot: None,
+            paused_at: None,
+            last_revealed: vec![],
         }
     }
 }
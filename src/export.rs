@@ -0,0 +1,135 @@
+//! Files written for the player to keep or share, such as save slots and replays.
+//!
+//! They all go into one folder next to the app's storage, under names that never replace
+//! an earlier export.
+
+use std::path::{Path, PathBuf};
+
+use crate::i18n::{tr, tr_args};
+use crate::replay::ReplayFormatError;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExportError {
+    /// The system has no folder for the app's data, such as on the web.
+    NoFolder,
+    /// The replay cannot be held by the format picked.
+    Replay(ReplayFormatError),
+    Io(String),
+}
+
+impl std::fmt::Display for ExportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoFolder => f.write_str(tr("export.no_folder")),
+            Self::Replay(err) => err.fmt(f),
+            Self::Io(message) => f.write_str(&tr_args("export.io", &[("message", message)])),
+        }
+    }
+}
+
+impl std::error::Error for ExportError {}
+
+impl From<std::io::Error> for ExportError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err.to_string())
+    }
+}
+
+/// The folder exports are written to.
+pub fn folder() -> Option<PathBuf> {
+    #[cfg(not(target_arch = "wasm32"))]
+    return eframe::storage_dir(crate::constants::APP_ID).map(|it| it.join("exports"));
+    #[cfg(target_arch = "wasm32")]
+    None
+}
+
+/// `name` with everything but letters, digits and `-` replaced, to be safe in a file name.
+pub fn file_stem(name: &str) -> String {
+    name.chars()
+        .map(|it| {
+            if it.is_alphanumeric() || it == '-' {
+                it
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Writes `data` into the export [`folder`] as `<stem>.<extension>`, numbered when that
+/// name is taken, and returns where it went.
+///
+/// # Errors
+///
+/// Fails when there is no export folder, or it or the file cannot be written.
+pub fn write(stem: &str, extension: &str, data: &[u8]) -> Result<PathBuf, ExportError> {
+    let folder = folder().ok_or(ExportError::NoFolder)?;
+    Ok(write_new(&folder, stem, extension, data)?)
+}
+
+fn write_new(folder: &Path, stem: &str, extension: &str, data: &[u8]) -> std::io::Result<PathBuf> {
+    use std::io::Write as _;
+
+    std::fs::create_dir_all(folder)?;
+    for number in 1.. {
+        let name = if number == 1 {
+            format!("{stem}.{extension}")
+        } else {
+            format!("{stem} ({number}).{extension}")
+        };
+        let path = folder.join(name);
+        // Creating the file only if it is new leaves no window to overwrite one.
+        match std::fs::File::create_new(&path) {
+            Ok(mut file) => {
+                file.write_all(data)?;
+                return Ok(path);
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => {}
+            Err(err) => return Err(err),
+        }
+    }
+    unreachable!("a free file name is found before the numbers run out")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{file_stem, write_new};
+
+    #[test]
+    fn file_stems_keep_letters_and_digits() {
+        assert_eq!(file_stem("expert run-2"), "expert_run-2", "space replaced");
+        assert_eq!(file_stem("../扫雷"), "___扫雷", "no path separators");
+    }
+
+    #[test]
+    fn never_replaces_an_earlier_export() {
+        let folder =
+            std::env::temp_dir().join(format!("minesweeper-export-{}", std::process::id()));
+        let first = write_new(&folder, "slot", "msave", b"first").expect("written");
+        let second = write_new(&folder, "slot", "msave", b"second").expect("written");
+        let third = write_new(&folder, "slot", "msave", b"third").expect("written");
+        let names: Vec<_> = [&first, &second, &third]
+            .iter()
+            .map(|it| it.file_name().and_then(|it| it.to_str()).map(str::to_owned))
+            .collect();
+        let contents: Vec<_> = [&first, &second, &third]
+            .iter()
+            .map(|it| std::fs::read(it).expect("readable"))
+            .collect();
+        std::fs::remove_dir_all(&folder).expect("cleaned up");
+        assert_eq!(
+            names,
+            [
+                Some("slot.msave".to_owned()),
+                Some("slot (2).msave".to_owned()),
+                Some("slot (3).msave".to_owned()),
+            ],
+            "numbered after the first"
+        );
+        assert_eq!(
+            contents,
+            [b"first".to_vec(), b"second".to_vec(), b"third".to_vec()],
+            "each file kept its own data"
+        );
+    }
+}
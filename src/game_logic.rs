@@ -1,62 +1,355 @@
+use crate::action::GameAction;
+use crate::config::BoardConfig;
 use crate::data::CellData;
 use crate::data::CellPos;
+use crate::data::CellRenderState;
 use crate::data::GameBoardData;
+use crate::data::RoundData;
+use crate::data::RoundEndingType;
+use crate::data::RoundState;
+use rand::seq::IndexedRandom as _;
 use rand::seq::SliceRandom as _;
 
-pub fn generate_new_board(board_size: usize, total_mine: usize) -> GameBoardData {
-    let mut mine_map = vec![false; board_size * board_size];
-    let slice = &mut mine_map[0..total_mine];
-    slice.fill(true);
-    let mut rng = rand::rng();
-    mine_map.shuffle(&mut rng);
-    let mine_map: Vec<Vec<bool>> = mine_map
-        .chunks(board_size)
-        .map(|slice| slice.to_vec())
+pub fn generate_new_board(config: BoardConfig) -> GameBoardData {
+    generate_board_avoiding(config.width(), config.height(), config.mines(), &[])
+}
+
+/// Places mines at random on any cell but the ones in `keep_clear`.
+pub fn generate_board_avoiding(
+    width: usize,
+    height: usize,
+    total_mine: usize,
+    keep_clear: &[CellPos],
+) -> GameBoardData {
+    let mut mines: Vec<CellPos> = (0..width * height)
+        .map(|index| CellPos::new(index / width, index % width))
+        .filter(|it| !keep_clear.contains(it))
         .collect();
+    let mut rng = rand::rng();
+    mines.shuffle(&mut rng);
+    mines.truncate(total_mine);
+    mines.sort_by_key(|it| (it.x, it.y));
 
-    // println!("Mine Map:{:?}",mine_map);
+    // board_data.show_mine_location();
+    // board_data.show_game_board();
+    board_from_mines(width, height, &mines)
+}
 
-    let max_index= board_size-1;
-    let mut result = vec![vec![CellData::default(); board_size]; board_size];
+/// Builds a fully covered board with mines at the given positions.
+pub fn board_from_mines(width: usize, height: usize, mines: &[CellPos]) -> GameBoardData {
+    let mut result = GameBoardData {
+        cells: (0..height)
+            .map(|j| {
+                (0..width)
+                    .map(|k| CellData {
+                        position: CellPos::new(j, k),
+                        ..Default::default()
+                    })
+                    .collect()
+            })
+            .collect(),
+        last_click: None,
+        revision: crate::data::next_revision(),
+    };
 
-    for j in 0..board_size {
-        for k in 0..board_size {
-            result[j][k].is_mine = mine_map[j][k];
-            result[j][k].position = CellPos::new(j, k);
-            if !mine_map[j][k] {
-                continue;
+    for mine in mines {
+        if let Some(cell) = result.get_cell_mut(mine) {
+            cell.is_mine = true;
+        }
+        for pos in result.get_surround_positions(mine) {
+            if let Some(cell) = result.get_cell_mut(&pos) {
+                cell.nearby_mines += 1;
             }
-            if j > 0 {
-                result[j - 1][k].nearby_mines += 1;
-                if k > 0 {
-                    result[j - 1][k - 1].nearby_mines += 1;
-                }
-                if k < max_index{
-                    result[j - 1][k + 1].nearby_mines += 1;
-                }
+        }
+    }
+    result
+}
+
+impl RoundData {
+    /// Applies a player action and records it in the round's replay.
+    pub fn perform(&mut self, action: GameAction) {
+        if !matches!(
+            self.round_state_type,
+            RoundState::NotStarted | RoundState::Playing
+        ) || self.is_paused()
+        {
+            return;
+        }
+        let time_ms = self.elapsed_ms();
+        if self.apply_action(&action) {
+            self.replay.record(time_ms, action);
+        }
+    }
+
+    /// Moves mines away from the first reveal so the round starts on an opening, or at least
+    /// on a safe cell when the board is too crowded for one. Does nothing once started or
+    /// on a fixed layout.
+    pub fn make_safe_opening(&mut self, pos: &CellPos) {
+        let revealable = self
+            .board_data
+            .get_cell(pos)
+            .is_some_and(|it| it.render_state == CellRenderState::Covered && !it.is_flagged);
+        if self.round_state_type != RoundState::NotStarted || self.fixed_layout || !revealable {
+            return;
+        }
+        let cell_count = self.board_width * self.board_height;
+        let mut keep_clear = self.board_data.get_surround_positions(pos);
+        keep_clear.push(pos.clone());
+        if cell_count.saturating_sub(keep_clear.len()) < self.total_mine {
+            keep_clear = vec![pos.clone()];
+        }
+        if cell_count.saturating_sub(keep_clear.len()) < self.total_mine
+            || !keep_clear
+                .iter()
+                .any(|it| self.board_data.get_cell(it).is_some_and(|it| it.is_mine))
+        {
+            return;
+        }
+        let mut board_data = generate_board_avoiding(
+            self.board_width,
+            self.board_height,
+            self.total_mine,
+            &keep_clear,
+        );
+        // Keep flags placed before the first reveal.
+        for cell in self.board_data.cells.iter().flatten() {
+            if let Some(it) = board_data.get_cell_mut(&cell.position) {
+                it.is_flagged = cell.is_flagged;
             }
-            if j < max_index{
-                result[j + 1][k].nearby_mines += 1;
-                if k > 0 {
-                    result[j + 1][k - 1].nearby_mines += 1;
-                }
-                if k < max_index{
-                    result[j + 1][k + 1].nearby_mines += 1;
-                }
+        }
+        self.replay.mines = board_data.get_mine_positions();
+        self.board_data = board_data;
+    }
+
+    /// Reveals a safe covered cell, preferring one next to the revealed area, or an opening
+    /// before the first reveal. Counts as help.
+    pub fn hint(&mut self) -> Option<CellPos> {
+        if !matches!(
+            self.round_state_type,
+            RoundState::NotStarted | RoundState::Playing
+        ) || self.is_paused()
+        {
+            return None;
+        }
+        let candidates: Vec<&CellData> = self
+            .board_data
+            .cells
+            .iter()
+            .flatten()
+            .filter(|it| {
+                it.render_state == CellRenderState::Covered && !it.is_flagged && !it.is_mine
+            })
+            .collect();
+        let on_frontier = |cell: &CellData| {
+            self.board_data
+                .get_surround_cells(&cell.position)
+                .iter()
+                .any(|it| it.render_state == CellRenderState::Revealed)
+        };
+        let best: Vec<CellPos> = [
+            candidates
+                .iter()
+                .filter(|it| on_frontier(it))
+                .map(|it| it.position.clone())
+                .collect::<Vec<_>>(),
+            candidates
+                .iter()
+                .filter(|it| it.nearby_mines == 0)
+                .map(|it| it.position.clone())
+                .collect(),
+            candidates.iter().map(|it| it.position.clone()).collect(),
+        ]
+        .into_iter()
+        .find(|it| !it.is_empty())?;
+        let pos = best.choose(&mut rand::rng())?.clone();
+        self.assisted = true;
+        self.perform(GameAction::Reveal(pos.clone()));
+        Some(pos)
+    }
+
+    /// Takes back the last action by playing the round again without it, which can also
+    /// take back the click on a mine. Counts as help.
+    pub fn undo(&mut self) -> bool {
+        let undoable = matches!(
+            self.round_state_type,
+            RoundState::NotStarted
+                | RoundState::Playing
+                | RoundState::Ended(RoundEndingType::ClickedMine(_))
+        );
+        if !undoable || self.is_paused() || self.replay.events.pop().is_none() {
+            return false;
+        }
+        let mut round = self.replay.initial_round();
+        for event in &self.replay.events {
+            round.apply_action(&event.action);
+        }
+        // The clock keeps running from the original first reveal.
+        self.board_data = round.board_data;
+        self.round_state_type = round.round_state_type;
+        self.assisted = true;
+        self.update_round_state();
+        true
+    }
+
+    /// Applies an action without recording it. Returns whether the board changed.
+    pub fn apply_action(&mut self, action: &GameAction) -> bool {
+        self.last_revealed.clear();
+        let changed = match action {
+            GameAction::Reveal(pos) => self.reveal_cell(pos),
+            GameAction::ToggleFlag(pos) => self.toggle_flag(pos),
+            GameAction::Chord(pos) => self.chord(pos),
+        };
+        if changed {
+            self.check_victory();
+        }
+        changed
+    }
+
+    fn toggle_flag(&mut self, pos: &CellPos) -> bool {
+        match self.board_data.get_cell_mut(pos) {
+            Some(cell) if cell.render_state == CellRenderState::Covered => {
+                cell.is_flagged = !cell.is_flagged;
+                true
             }
-            if k > 0 {
-                result[j][k - 1].nearby_mines += 1;
+            _ => false,
+        }
+    }
+
+    fn chord(&mut self, pos: &CellPos) -> bool {
+        let Some(origin_cell) = self.board_data.get_cell(pos) else {
+            return false;
+        };
+        if origin_cell.render_state != CellRenderState::Revealed || origin_cell.nearby_mines == 0 {
+            return false;
+        }
+        let surround_cells = self.board_data.get_surround_cells(pos);
+        if origin_cell.nearby_mines != surround_cells.iter().filter(|it| it.is_flagged).count() {
+            return false;
+        }
+        let mut changed = false;
+        for it in &surround_cells {
+            if !it.is_flagged {
+                changed |= self.reveal_cell(&it.position);
             }
-            if k < max_index{
-                result[j][k + 1].nearby_mines += 1;
+        }
+        changed
+    }
+
+    fn reveal_cell(&mut self, pos: &CellPos) -> bool {
+        let Some(cell) = self.board_data.get_cell(pos) else {
+            return false;
+        };
+        if cell.render_state == CellRenderState::Revealed || cell.is_flagged {
+            return false;
+        }
+
+        if self.round_state_type == RoundState::NotStarted {
+            self.start_time = chrono::Utc::now().timestamp_millis();
+            self.round_state_type = RoundState::Playing;
+        }
+
+        if cell.is_mine {
+            self.round_state_type = RoundState::Ended(RoundEndingType::ClickedMine(pos.clone()));
+            if let Some(cell) = self.board_data.get_cell_mut(pos) {
+                cell.render_state = CellRenderState::Revealed;
             }
+            return true;
         }
+
+        // Flood fill from the revealed cell, opening every cell next to a zero.
+        let mut pending = vec![pos.clone()];
+        while let Some(current) = pending.pop() {
+            let Some(cell) = self.board_data.get_cell_mut(&current) else {
+                continue;
+            };
+            if cell.render_state == CellRenderState::Revealed || cell.is_flagged || cell.is_mine {
+                continue;
+            }
+            cell.render_state = CellRenderState::Revealed;
+            let opens_neighbours = cell.nearby_mines == 0;
+            if opens_neighbours {
+                pending.extend(self.board_data.get_surround_positions(&current));
+            }
+            self.last_revealed.push(current);
+        }
+        true
     }
-    
-    // board_data.show_mine_location();
-    // board_data.show_game_board();
-    GameBoardData {
-        cells: result,
-        last_click: None,
+
+    fn check_victory(&mut self) {
+        if self.round_state_type == RoundState::Playing
+            && self.board_data.get_remain_cell_count() == self.total_mine
+        {
+            self.time_passed = self.elapsed_ms() / 1000;
+            self.round_state_type = RoundState::Ended(RoundEndingType::Victory);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::action::GameAction;
+    use crate::data::{CellPos, RoundData, RoundEndingType, RoundState};
+    use crate::metrics::RoundMetrics;
+
+    /// A position loaded part played, with the top-left mine flagged.
+    fn imported() -> RoundData {
+        let board = crate::board_format::read("F1..\n11..\n...*\n").expect("valid board");
+        RoundData::from_board_state(board)
+    }
+
+    #[test]
+    fn undo_keeps_an_imported_position() {
+        let mut round = imported();
+        let position = crate::board_format::write(&round.board_data, true);
+        round.perform(GameAction::ToggleFlag(CellPos::new(2, 3)));
+        round.perform(GameAction::Reveal(CellPos::new(0, 3)));
+        assert!(round.undo(), "reveal undone");
+        assert!(round.undo(), "flag undone");
+        assert_eq!(
+            crate::board_format::write(&round.board_data, true),
+            position,
+            "back to the imported position"
+        );
+        assert_eq!(round.round_state_type, RoundState::Playing, "still in play");
+        assert!(!round.undo(), "nothing before the import to undo");
+    }
+
+    #[test]
+    fn keeps_the_cells_the_last_action_opened() {
+        let mut round = imported();
+        let revision = round.board_data.revision();
+        round.perform(GameAction::Reveal(CellPos::new(0, 3)));
+        let mut opened = round.last_revealed.clone();
+        opened.sort_by_key(|it| (it.x, it.y));
+        assert_eq!(
+            opened,
+            [
+                CellPos::new(0, 2),
+                CellPos::new(0, 3),
+                CellPos::new(1, 2),
+                CellPos::new(1, 3)
+            ],
+            "the opening, without the numbers shown before"
+        );
+        assert_ne!(round.board_data.revision(), revision, "board changed");
+        round.perform(GameAction::ToggleFlag(CellPos::new(2, 3)));
+        assert!(round.last_revealed.is_empty(), "a flag opens nothing");
+    }
+
+    #[test]
+    fn imported_position_replays_from_where_it_was_loaded() {
+        let mut round = imported();
+        assert!(round.recorded, "a part played round never counts");
+        round.perform(GameAction::Reveal(CellPos::new(0, 3)));
+        round.perform(GameAction::Reveal(CellPos::new(2, 0)));
+        assert_eq!(
+            round.round_state_type,
+            RoundState::Ended(RoundEndingType::Victory),
+            "won"
+        );
+        assert!(
+            RoundMetrics::from_replay(&round.replay).won,
+            "replay wins too"
+        );
     }
-}
\ No newline at end of file
+}
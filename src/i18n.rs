@@ -0,0 +1,186 @@
+//! Translated text for everything the player reads, kept in the translation files under
+//! `assets/i18n/`.
+//!
+//! A file maps keys to text, where `{name}` stands for a value filled in by [`tr_args`].
+//! Keys missing from a translation fall back to English, so a new string only has to be
+//! added to `en.ron` before the other files catch up.
+
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Storage key of the picked [`Locale`], where `None` follows the system.
+pub const LOCALE_KEY: &str = "locale";
+
+/// The language in use, shared with code that has no [`egui::Context`] at hand, such as
+/// the `Display` impls of errors.
+static CURRENT: AtomicU8 = AtomicU8::new(0);
+
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Locale {
+    #[default]
+    English,
+    /// Simplified Chinese.
+    Chinese,
+}
+
+impl Locale {
+    pub const ALL: [Self; 2] = [Self::English, Self::Chinese];
+
+    /// The name of the language in itself, so it can be found whatever the current one is.
+    pub fn native_name(self) -> &'static str {
+        match self {
+            Self::English => "English",
+            Self::Chinese => "中文",
+        }
+    }
+    /// The locale for a BCP 47 tag or POSIX locale name such as `zh-CN` or `en_US.UTF-8`.
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let language = tag
+            .split(['-', '_', '.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match language.as_str() {
+            "en" => Some(Self::English),
+            "zh" => Some(Self::Chinese),
+            _ => None,
+        }
+    }
+    /// The first supported language of the operating system that [`Self::has_font`], from
+    /// the locale variables on native and the browser's language list on the web, or
+    /// English when none is.
+    pub fn detect() -> Self {
+        #[cfg(not(target_arch = "wasm32"))]
+        let tags: Vec<String> = ["LC_ALL", "LC_MESSAGES", "LANGUAGE", "LANG"]
+            .into_iter()
+            .filter_map(|it| std::env::var(it).ok())
+            .flat_map(|it| it.split(':').map(str::to_owned).collect::<Vec<_>>())
+            .collect();
+        #[cfg(target_arch = "wasm32")]
+        let tags: Vec<String> = web_sys::window()
+            .map(|it| it.navigator().languages())
+            .map(|it| it.iter().filter_map(|it| it.as_string()).collect())
+            .unwrap_or_default();
+        tags.iter()
+            .find_map(|it| Self::from_tag(it).filter(|it| it.has_font()))
+            .unwrap_or_default()
+    }
+
+    pub fn current() -> Self {
+        match CURRENT.load(Ordering::Relaxed) {
+            1 => Self::Chinese,
+            _ => Self::English,
+        }
+    }
+    /// Switches every later [`tr`] to this locale.
+    pub fn set_current(self) {
+        CURRENT.store(self as u8, Ordering::Relaxed);
+    }
+
+    fn table(self) -> &'static BTreeMap<String, String> {
+        static ENGLISH: OnceLock<BTreeMap<String, String>> = OnceLock::new();
+        static CHINESE: OnceLock<BTreeMap<String, String>> = OnceLock::new();
+        let (table, source) = match self {
+            Self::English => (&ENGLISH, include_str!("../assets/i18n/en.ron")),
+            Self::Chinese => (&CHINESE, include_str!("../assets/i18n/zh.ron")),
+        };
+        table.get_or_init(|| {
+            ron::from_str(source).unwrap_or_else(|err| {
+                log::error!("Broken translation file for {self:?}: {err}");
+                BTreeMap::new()
+            })
+        })
+    }
+    /// Whether the default fonts lack the glyphs of this language.
+    fn needs_cjk_font(self) -> bool {
+        self == Self::Chinese
+    }
+    /// Whether text in this language can be drawn, by the default fonts or by a system font
+    /// [`ensure_fonts`] can add. Never for Chinese on the web, where no system font can be
+    /// read.
+    pub fn has_font(self) -> bool {
+        if !self.needs_cjk_font() {
+            return true;
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            static FOUND: OnceLock<bool> = OnceLock::new();
+            *FOUND.get_or_init(|| CJK_FONTS.iter().any(|it| std::path::Path::new(it).exists()))
+        }
+        #[cfg(target_arch = "wasm32")]
+        false
+    }
+}
+
+/// The text under `key` in the current locale, or in English when it has none. Unknown
+/// keys come back as they are, so they stand out on screen.
+pub fn tr(key: &'static str) -> &'static str {
+    let current = Locale::current();
+    current
+        .table()
+        .get(key)
+        .or_else(|| Locale::English.table().get(key))
+        .map_or(key, String::as_str)
+}
+
+/// The text under `key` with each `{name}` replaced by the value given for it.
+pub fn tr_args(key: &'static str, args: &[(&str, &dyn std::fmt::Display)]) -> String {
+    args.iter().fold(tr(key).to_owned(), |text, (name, value)| {
+        text.replace(&format!("{{{name}}}"), &value.to_string())
+    })
+}
+
+/// Font files with Chinese glyphs that come with common systems, tried in order.
+#[cfg(not(target_arch = "wasm32"))]
+const CJK_FONTS: [&str; 9] = [
+    "C:\\Windows\\Fonts\\msyh.ttc",
+    "C:\\Windows\\Fonts\\simsun.ttc",
+    "/System/Library/Fonts/PingFang.ttc",
+    "/System/Library/Fonts/Hiragino Sans GB.ttc",
+    "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/google-noto-cjk/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/truetype/wqy/wqy-microhei.ttc",
+    "/usr/share/fonts/wenquanyi/wqy-microhei/wqy-microhei.ttc",
+];
+
+/// Adds a system font with the glyphs of `locale` behind egui's own, once one is needed.
+///
+/// The default fonts only cover Latin, Greek and Cyrillic. A locale without
+/// [`Locale::has_font`] is never put in use, so this only fails if the font goes missing.
+#[cfg_attr(target_arch = "wasm32", expect(unused_variables))]
+pub fn ensure_fonts(ctx: &egui::Context, locale: Locale) {
+    static INSTALLED: OnceLock<()> = OnceLock::new();
+    if !locale.needs_cjk_font() || INSTALLED.get().is_some() {
+        return;
+    }
+    INSTALLED.get_or_init(|| {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let Some((path, data)) = CJK_FONTS
+                .into_iter()
+                .find_map(|path| Some((path, std::fs::read(path).ok()?)))
+            else {
+                log::warn!("No font with Chinese glyphs found");
+                return;
+            };
+            log::info!("Using {path} for Chinese text");
+            let mut fonts = egui::FontDefinitions::default();
+            fonts.font_data.insert(
+                "cjk".to_owned(),
+                std::sync::Arc::new(egui::FontData::from_owned(data)),
+            );
+            for family in [egui::FontFamily::Proportional, egui::FontFamily::Monospace] {
+                fonts
+                    .families
+                    .entry(family)
+                    .or_default()
+                    .push("cjk".to_owned());
+            }
+            ctx.set_fonts(fonts);
+        }
+        #[cfg(target_arch = "wasm32")]
+        log::warn!("No font with Chinese glyphs on the web");
+    });
+}
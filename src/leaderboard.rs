@@ -0,0 +1,213 @@
+//! The fastest unassisted wins of each difficulty, with their replays, and the name new
+//! entries are made under.
+
+use std::collections::BTreeMap;
+
+use crate::metrics::RoundMetrics;
+use crate::replay::Replay;
+use crate::statistics::Difficulty;
+
+/// Storage key of the [`Leaderboard`].
+pub const LEADERBOARD_KEY: &str = "leaderboard";
+/// Entries kept per difficulty.
+pub const LEADERBOARD_SIZE: usize = 10;
+
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug)]
+pub struct LeaderboardEntry {
+    pub player_name: String,
+    pub time_ms: u32,
+    pub bbbv_per_second: f32,
+    /// Unix timestamp in milliseconds of the first reveal.
+    pub played_at: i64,
+    pub replay: Replay,
+}
+
+impl LeaderboardEntry {
+    pub fn date(&self) -> String {
+        chrono::DateTime::from_timestamp_millis(self.played_at).map_or_else(String::new, |it| {
+            it.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+    }
+}
+
+/// Fastest unassisted wins per [`Difficulty`], best first.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug)]
+#[serde(default)]
+pub struct Leaderboard {
+    pub player_name: String,
+    pub by_difficulty: BTreeMap<Difficulty, Vec<LeaderboardEntry>>,
+}
+
+impl Default for Leaderboard {
+    fn default() -> Self {
+        Self {
+            player_name: "Player".to_owned(),
+            by_difficulty: BTreeMap::new(),
+        }
+    }
+}
+
+impl Leaderboard {
+    pub fn entries(&self, difficulty: Difficulty) -> &[LeaderboardEntry] {
+        self.by_difficulty
+            .get(&difficulty)
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Adds a won round under the current player name and returns its 0-based rank, or
+    /// `None` if it did not make the top [`LEADERBOARD_SIZE`].
+    pub fn submit(
+        &mut self,
+        difficulty: Difficulty,
+        played_at: i64,
+        replay: &Replay,
+        metrics: &RoundMetrics,
+    ) -> Option<usize> {
+        let entries = self.by_difficulty.entry(difficulty).or_default();
+        // Ties keep the older entry ahead.
+        let rank = entries.partition_point(|it| it.time_ms <= metrics.time_ms);
+        if rank >= LEADERBOARD_SIZE {
+            return None;
+        }
+        entries.insert(
+            rank,
+            LeaderboardEntry {
+                player_name: self.player_name.clone(),
+                time_ms: metrics.time_ms,
+                bbbv_per_second: metrics.bbbv_per_second(),
+                played_at,
+                replay: replay.clone(),
+            },
+        );
+        entries.truncate(LEADERBOARD_SIZE);
+        Some(rank)
+    }
+
+    /// Whether the entry at `rank` beats every other entry of the same player.
+    pub fn is_personal_best(&self, difficulty: Difficulty, rank: usize) -> bool {
+        let entries = self.entries(difficulty);
+        let Some(entry) = entries.get(rank) else {
+            return false;
+        };
+        !entries
+            .iter()
+            .take(rank)
+            .any(|it| it.player_name == entry.player_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LEADERBOARD_SIZE, Leaderboard};
+    use crate::metrics::RoundMetrics;
+    use crate::replay::Replay;
+    use crate::statistics::Difficulty;
+
+    /// Submits a win in `time_ms` as `player`, played at `played_at`, the way the app
+    /// does, and returns its rank and whether it earns the personal-best banner.
+    fn submit(
+        leaderboard: &mut Leaderboard,
+        player: &str,
+        time_ms: u32,
+        played_at: i64,
+    ) -> (Option<usize>, bool) {
+        leaderboard.player_name = player.to_owned();
+        let metrics = RoundMetrics {
+            time_ms,
+            won: true,
+            ..Default::default()
+        };
+        let replay = Replay::new(9, 9, vec![]);
+        let rank = leaderboard.submit(Difficulty::Beginner, played_at, &replay, &metrics);
+        let banner =
+            rank.is_some_and(|rank| leaderboard.is_personal_best(Difficulty::Beginner, rank));
+        (rank, banner)
+    }
+
+    fn times(leaderboard: &Leaderboard) -> Vec<(u32, i64)> {
+        leaderboard
+            .entries(Difficulty::Beginner)
+            .iter()
+            .map(|it| (it.time_ms, it.played_at))
+            .collect()
+    }
+
+    #[test]
+    fn ranks_fastest_first_and_ties_keep_the_older_entry_ahead() {
+        let mut leaderboard = Leaderboard::default();
+        assert_eq!(
+            submit(&mut leaderboard, "a", 20, 1).0,
+            Some(0),
+            "first entry"
+        );
+        assert_eq!(submit(&mut leaderboard, "a", 10, 2).0, Some(0), "faster");
+        assert_eq!(submit(&mut leaderboard, "a", 30, 3).0, Some(2), "slowest");
+        assert_eq!(submit(&mut leaderboard, "a", 20, 4).0, Some(2), "tie");
+        assert_eq!(
+            times(&leaderboard),
+            [(10, 2), (20, 1), (20, 4), (30, 3)],
+            "sorted by time, then by age"
+        );
+        assert!(
+            leaderboard.entries(Difficulty::Expert).is_empty(),
+            "other difficulties are untouched"
+        );
+    }
+
+    #[test]
+    fn keeps_only_the_fastest_entries() {
+        let mut leaderboard = Leaderboard::default();
+        let slowest = u32::try_from(LEADERBOARD_SIZE).expect("small") * 10;
+        for time in (1..=LEADERBOARD_SIZE).map(|it| u32::try_from(it).expect("small") * 10) {
+            submit(&mut leaderboard, "a", time, 0);
+        }
+        assert_eq!(
+            submit(&mut leaderboard, "a", slowest, 1).0,
+            None,
+            "a tie with the last entry ranks after it"
+        );
+        assert_eq!(submit(&mut leaderboard, "a", 5, 2).0, Some(0), "fastest");
+        let entries = leaderboard.entries(Difficulty::Beginner);
+        assert_eq!(entries.len(), LEADERBOARD_SIZE, "truncated");
+        assert!(
+            entries.iter().all(|it| it.time_ms < slowest),
+            "the slowest entry was dropped"
+        );
+    }
+
+    #[test]
+    fn personal_best_only_when_no_faster_entry_of_the_same_player() {
+        let mut leaderboard = Leaderboard::default();
+        assert_eq!(
+            submit(&mut leaderboard, "a", 20, 1),
+            (Some(0), true),
+            "first"
+        );
+        assert_eq!(
+            submit(&mut leaderboard, "a", 30, 2),
+            (Some(1), false),
+            "slower than a's best"
+        );
+        assert_eq!(
+            submit(&mut leaderboard, "b", 40, 3),
+            (Some(2), true),
+            "b's first entry, behind a"
+        );
+        assert_eq!(
+            submit(&mut leaderboard, "b", 40, 4),
+            (Some(3), false),
+            "a tie with b's best"
+        );
+        assert_eq!(
+            submit(&mut leaderboard, "a", 10, 5),
+            (Some(0), true),
+            "a beats their own best"
+        );
+        assert!(
+            !leaderboard.is_personal_best(Difficulty::Beginner, LEADERBOARD_SIZE),
+            "no entry at that rank"
+        );
+    }
+}
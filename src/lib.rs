@@ -2,10 +2,23 @@
 
 pub mod action;
 pub mod app;
+pub mod bindings;
+pub mod board_format;
+pub mod config;
 pub mod constants;
 pub mod data;
+pub mod export;
 pub mod game_logic;
+pub mod i18n;
+pub mod leaderboard;
+pub mod metrics;
+pub mod replay;
+pub mod save;
 pub mod scenes;
+pub mod share;
+pub mod skin;
+pub mod statistics;
+pub mod theme;
 pub mod utils;
 pub mod widgets;
 pub use app::MineSweeper;
@@ -2,12 +2,17 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")] // hide console window on Windows in release
 
 use minesweeper::MineSweeper;
+#[cfg(not(target_arch = "wasm32"))]
+use minesweeper::config::BoardConfig;
 
 // When compiling natively:
 #[cfg(not(target_arch = "wasm32"))]
 fn main() -> eframe::Result {
     env_logger::init(); // Log to stderr (if you run with `RUST_LOG=debug`).
 
+    let board = board_from_args(std::env::args().skip(1))
+        .map_err(|err| eframe::Error::AppCreation(err.into()))?;
+
     let native_options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([400.0, 500.0])
@@ -23,15 +28,57 @@ fn main() -> eframe::Result {
         ..Default::default()
     };
     eframe::run_native(
-        "MineSweeper",
+        minesweeper::constants::APP_ID,
         native_options,
         Box::new(|cc| {
             egui_extras::install_image_loaders(&cc.egui_ctx);
-            Ok(Box::new(MineSweeper::new(cc)))
+            let mut app = MineSweeper::new(cc);
+            if let Some(board) = board {
+                app.start_custom_board(board)?;
+            }
+            Ok(Box::new(app))
         }),
     )
 }
 
+/// Reads `--width`, `--height` and `--mines`. Any of them starts a custom board, with the
+/// missing ones taken from the default board.
+#[cfg(not(target_arch = "wasm32"))]
+fn board_from_args(mut args: impl Iterator<Item = String>) -> Result<Option<BoardConfig>, String> {
+    let default = BoardConfig::default();
+    let (mut width, mut height, mut mines) = (None, None, None);
+    while let Some(flag) = args.next() {
+        let target = match flag.as_str() {
+            "--width" => &mut width,
+            "--height" => &mut height,
+            "--mines" => &mut mines,
+            _ => {
+                return Err(format!(
+                    "unknown argument `{flag}`, expected --width, --height or --mines"
+                ));
+            }
+        };
+        let value = args
+            .next()
+            .ok_or_else(|| format!("`{flag}` needs a value"))?;
+        *target = Some(
+            value
+                .parse::<usize>()
+                .map_err(|err| format!("`{flag} {value}`: {err}"))?,
+        );
+    }
+    if (width, height, mines) == (None, None, None) {
+        return Ok(None);
+    }
+    BoardConfig::new(
+        width.unwrap_or(default.width()),
+        height.unwrap_or(default.height()),
+        mines.unwrap_or(default.mines()),
+    )
+    .map(Some)
+    .map_err(|err| err.to_string())
+}
+
 // When compiling to web using trunk:
 #[cfg(target_arch = "wasm32")]
 fn main() {
@@ -42,6 +89,20 @@ fn main() {
 
     let web_options = eframe::WebOptions::default();
 
+    // A board shared as `index.html#v1-…` starts right away.
+    let shared_board = web_sys::window()
+        .and_then(|window| window.location().hash().ok())
+        .filter(|hash| !hash.is_empty())
+        .and_then(
+            |hash| match minesweeper::share::SharedBoard::from_fragment(&hash) {
+                Ok(board) => Some(board),
+                Err(err) => {
+                    log::debug!("Ignoring URL fragment {hash}: {err}");
+                    None
+                }
+            },
+        );
+
     wasm_bindgen_futures::spawn_local(async {
         let document = web_sys::window()
             .expect("No window")
@@ -58,9 +119,13 @@ fn main() {
             .start(
                 canvas,
                 web_options,
-                Box::new(|cc| {
+                Box::new(move |cc| {
                     egui_extras::install_image_loaders(&cc.egui_ctx);
-                    return Ok(Box::new(crate::MineSweeper::new(cc)));
+                    let mut app = crate::MineSweeper::new(cc);
+                    if let Some(board) = &shared_board {
+                        app.start_shared_board(board);
+                    }
+                    Ok(Box::new(app))
                 }),
             )
             .await;
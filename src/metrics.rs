@@ -0,0 +1,221 @@
+//! Scores of a round, worked out from its replay alone so that imported recordings are
+//! scored like rounds played here: 3BV, the share of it solved, clicks and efficiency.
+
+use crate::action::GameAction;
+use crate::data::{CellPos, CellRenderState, GameBoardData, RoundEndingType, RoundState};
+use crate::replay::Replay;
+
+/// Scores of a finished or partially played round, derived from its replay.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct RoundMetrics {
+    /// Minimum number of clicks needed to clear the board (3BV).
+    pub bbbv: usize,
+    /// 3BV worth of the board that was actually cleared.
+    pub solved_bbbv: usize,
+    pub time_ms: u32,
+    pub left_clicks: usize,
+    pub right_clicks: usize,
+    pub chords: usize,
+    pub won: bool,
+}
+
+impl RoundMetrics {
+    pub fn from_replay(replay: &Replay) -> Self {
+        let mut round = replay.initial_round();
+        let bbbv = bbbv(&round.board_data);
+        let mut metrics = Self {
+            bbbv,
+            time_ms: replay.events.last().map_or(0, |it| it.time_ms),
+            ..Default::default()
+        };
+        for event in &replay.events {
+            match event.action {
+                GameAction::Reveal(_) => metrics.left_clicks += 1,
+                GameAction::ToggleFlag(_) => metrics.right_clicks += 1,
+                GameAction::Chord(_) => metrics.chords += 1,
+            }
+            round.apply_action(&event.action);
+        }
+        metrics.won = round.round_state_type == RoundState::Ended(RoundEndingType::Victory);
+        metrics.solved_bbbv = if metrics.won {
+            bbbv
+        } else {
+            solved_bbbv(&round.board_data)
+        };
+        metrics
+    }
+    pub fn clicks(&self) -> usize {
+        self.left_clicks + self.right_clicks + self.chords
+    }
+    pub fn bbbv_per_second(&self) -> f32 {
+        if self.time_ms == 0 {
+            return 0.0;
+        }
+        self.solved_bbbv as f32 * 1000.0 / self.time_ms as f32
+    }
+    /// Solved 3BV per click, as a percentage.
+    pub fn efficiency(&self) -> f32 {
+        if self.clicks() == 0 {
+            return 0.0;
+        }
+        self.solved_bbbv as f32 * 100.0 / self.clicks() as f32
+    }
+}
+
+/// The board's 3BV: one click per opening plus one per number not bordering an opening.
+pub fn bbbv(board: &GameBoardData) -> usize {
+    count_bbbv(board, |_| true)
+}
+
+/// 3BV of the openings and isolated numbers that are already fully revealed.
+pub fn solved_bbbv(board: &GameBoardData) -> usize {
+    count_bbbv(board, |cells| {
+        cells.iter().all(|it| {
+            board
+                .get_cell(it)
+                .is_some_and(|it| it.render_state == CellRenderState::Revealed)
+        })
+    })
+}
+
+/// Counts the board's 3BV groups (openings with their border, and lone numbers) that
+/// satisfy `counted`.
+fn count_bbbv(board: &GameBoardData, counted: impl Fn(&[CellPos]) -> bool) -> usize {
+    let mut visited = vec![vec![false; board.width()]; board.height()];
+    let mut result = 0;
+
+    // Openings first, so their numbered border is not counted twice.
+    for cell in board.cells.iter().flatten() {
+        if cell.is_mine || cell.nearby_mines != 0 || mark_visited(&mut visited, &cell.position) {
+            continue;
+        }
+        let mut group = vec![cell.position.clone()];
+        let mut pending = vec![cell.position.clone()];
+        while let Some(current) = pending.pop() {
+            for pos in board.get_surround_positions(&current) {
+                let Some(neighbour) = board.get_cell(&pos) else {
+                    continue;
+                };
+                if neighbour.is_mine || mark_visited(&mut visited, &pos) {
+                    continue;
+                }
+                if neighbour.nearby_mines == 0 {
+                    pending.push(pos.clone());
+                }
+                group.push(pos);
+            }
+        }
+        if counted(&group) {
+            result += 1;
+        }
+    }
+
+    for cell in board.cells.iter().flatten() {
+        if cell.is_mine || mark_visited(&mut visited, &cell.position) {
+            continue;
+        }
+        if counted(std::slice::from_ref(&cell.position)) {
+            result += 1;
+        }
+    }
+    result
+}
+
+/// Marks `pos` as visited, returning whether it already was (or lies off the board).
+fn mark_visited(visited: &mut [Vec<bool>], pos: &CellPos) -> bool {
+    match visited.get_mut(pos.x).and_then(|row| row.get_mut(pos.y)) {
+        Some(it) if !*it => {
+            *it = true;
+            false
+        }
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RoundMetrics, bbbv};
+    use crate::action::GameAction;
+    use crate::data::{CellPos, GameBoardData};
+    use crate::replay::Replay;
+
+    fn board(text: &str) -> GameBoardData {
+        crate::board_format::read(text).expect("valid board")
+    }
+
+    fn replay(text: &str, actions: &[GameAction]) -> Replay {
+        let board = board(text);
+        let mut replay = Replay::new(board.width(), board.height(), board.get_mine_positions());
+        for (index, action) in actions.iter().enumerate() {
+            replay.record(index as u32 * 1000, action.clone());
+        }
+        replay
+    }
+
+    /// Two mines above an opening of four cells, with six numbers away from it.
+    const NUMBERS: &str = ".*.*...\n.......\n";
+
+    #[test]
+    fn openings_count_once_with_their_border() {
+        // A wall of mines between two openings of six cells each.
+        assert_eq!(
+            bbbv(&board("...*...\n...*...\n...*...\n")),
+            2,
+            "two openings"
+        );
+        // The opening and the two numbers on its edge make one, the other six one each.
+        assert_eq!(bbbv(&board(NUMBERS)), 7, "opening and isolated numbers");
+    }
+
+    #[test]
+    fn partly_solved_rounds_score_what_was_cleared() {
+        let metrics = RoundMetrics::from_replay(&replay(
+            NUMBERS,
+            &[
+                GameAction::Reveal(CellPos::new(0, 6)),
+                GameAction::ToggleFlag(CellPos::new(0, 1)),
+                GameAction::Reveal(CellPos::new(0, 0)),
+            ],
+        ));
+        assert_eq!(metrics.bbbv, 7, "3BV of the whole board");
+        assert_eq!(metrics.solved_bbbv, 2, "the opening and one number");
+        assert!(!metrics.won, "round still in play");
+        assert_eq!(
+            (metrics.left_clicks, metrics.right_clicks, metrics.chords),
+            (2, 1, 0),
+            "clicks by kind"
+        );
+        assert_eq!(metrics.time_ms, 2000, "time of the last action");
+        assert!(
+            (metrics.bbbv_per_second() - 1.0).abs() < f32::EPSILON,
+            "3BV/s is {}",
+            metrics.bbbv_per_second()
+        );
+        assert!(
+            (metrics.efficiency() - 200.0 / 3.0).abs() < 0.01,
+            "efficiency is {}",
+            metrics.efficiency()
+        );
+    }
+
+    #[test]
+    fn won_rounds_solve_the_whole_board() {
+        let metrics = RoundMetrics::from_replay(&replay(
+            "...*...\n...*...\n...*...\n",
+            &[
+                GameAction::Reveal(CellPos::new(0, 0)),
+                GameAction::Reveal(CellPos::new(2, 6)),
+            ],
+        ));
+        assert!(metrics.won, "both openings cleared");
+        assert_eq!(metrics.solved_bbbv, metrics.bbbv, "all of the 3BV solved");
+    }
+
+    #[test]
+    fn rounds_loaded_from_a_position_count_it_as_solved() {
+        let mut replay = replay(NUMBERS, &[GameAction::Reveal(CellPos::new(0, 0))]);
+        replay.revealed = vec![CellPos::new(1, 0)];
+        let metrics = RoundMetrics::from_replay(&replay);
+        assert_eq!(metrics.solved_bbbv, 2, "loaded number and revealed number");
+    }
+}
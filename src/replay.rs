@@ -0,0 +1,430 @@
+pub mod avf;
+pub mod mouse;
+pub mod rawvf;
+
+use crate::action::GameAction;
+use crate::config::ConfigError;
+use crate::data::{CellPos, CellRenderState, RoundData};
+use crate::i18n::{tr, tr_args};
+use crate::metrics::RoundMetrics;
+
+/// Speeds offered by the replay viewer, slowest first.
+pub const REPLAY_SPEEDS: [f32; 6] = [0.25, 0.5, 1.0, 2.0, 4.0, 8.0];
+
+/// How long the cursor trail stays visible behind the pointer.
+pub const CURSOR_TRAIL_MS: u32 = 600;
+/// Shortest time between two cursor samples, which keeps the trail of a long round small
+/// enough for the leaderboard and save slots.
+const CURSOR_SAMPLE_MS: u32 = 30;
+/// Shortest move in cells worth another cursor sample.
+const CURSOR_SAMPLE_CELLS: f32 = 0.125;
+
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct ReplayEvent {
+    /// Milliseconds since the first reveal.
+    pub time_ms: u32,
+    pub action: GameAction,
+}
+
+/// Pointer position over the board, measured in cells from the top-left corner.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq)]
+pub struct CursorSample {
+    pub time_ms: u32,
+    pub column: f32,
+    pub row: f32,
+}
+
+/// Everything needed to play a round back: the mine layout and the player's inputs.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, Default, PartialEq)]
+pub struct Replay {
+    pub width: usize,
+    pub height: usize,
+    pub mines: Vec<CellPos>,
+    /// Cells already revealed before the first event, for rounds loaded from a position
+    /// such as a [`crate::board_format`] file.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub revealed: Vec<CellPos>,
+    /// Cells already flagged before the first event.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub flagged: Vec<CellPos>,
+    pub events: Vec<ReplayEvent>,
+    pub cursor: Vec<CursorSample>,
+}
+
+impl Replay {
+    pub fn new(width: usize, height: usize, mines: Vec<CellPos>) -> Self {
+        Self {
+            width,
+            height,
+            mines,
+            revealed: vec![],
+            flagged: vec![],
+            events: vec![],
+            cursor: vec![],
+        }
+    }
+    pub fn record(&mut self, time_ms: u32, action: GameAction) {
+        self.events.push(ReplayEvent { time_ms, action });
+    }
+    /// Adds a cursor sample, unless it comes too soon after the last one or the pointer has
+    /// barely moved since.
+    pub fn record_cursor(&mut self, time_ms: u32, column: f32, row: f32) {
+        let due = self.cursor.last().is_none_or(|last| {
+            let moved = (last.column - column).abs().max((last.row - row).abs());
+            time_ms >= last.time_ms + CURSOR_SAMPLE_MS && moved >= CURSOR_SAMPLE_CELLS
+        });
+        if due {
+            self.cursor.push(CursorSample {
+                time_ms,
+                column,
+                row,
+            });
+        }
+    }
+    pub fn duration_ms(&self) -> u32 {
+        let last_event = self.events.last().map_or(0, |it| it.time_ms);
+        let last_cursor = self.cursor.last().map_or(0, |it| it.time_ms);
+        last_event.max(last_cursor)
+    }
+    /// The round on this replay's board before the first event: untouched, or at the
+    /// position it was loaded from.
+    pub fn initial_round(&self) -> RoundData {
+        let mut board = crate::game_logic::board_from_mines(self.width, self.height, &self.mines);
+        if self.revealed.is_empty() && self.flagged.is_empty() {
+            return RoundData::from_board(self.mines.len(), board);
+        }
+        for pos in &self.revealed {
+            if let Some(cell) = board.get_cell_mut(pos) {
+                cell.render_state = CellRenderState::Revealed;
+            }
+        }
+        for pos in &self.flagged {
+            if let Some(cell) = board.get_cell_mut(pos) {
+                cell.is_flagged = true;
+            }
+        }
+        RoundData::from_board_state(board)
+    }
+    /// Cursor samples recorded in the trail window ending at `time_ms`, oldest first.
+    pub fn cursor_trail(&self, time_ms: u32) -> &[CursorSample] {
+        let end = self.cursor.partition_point(|it| it.time_ms <= time_ms);
+        let start = self
+            .cursor
+            .partition_point(|it| it.time_ms + CURSOR_TRAIL_MS < time_ms);
+        self.cursor.get(start..end).unwrap_or_default()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReplayFormatError {
+    UnexpectedEnd,
+    UnknownLevel(u8),
+    MineOutOfBounds {
+        row: usize,
+        column: usize,
+    },
+    DuplicateMine {
+        row: usize,
+        column: usize,
+    },
+    MissingField(&'static str),
+    Syntax {
+        line: usize,
+        problem: SyntaxProblem,
+    },
+    BoardTooLarge,
+    /// The replay starts from a loaded position, which the community formats cannot hold.
+    StartsFromPosition,
+    UnknownFormat(String),
+    InvalidBoard(ConfigError),
+}
+
+impl std::fmt::Display for ReplayFormatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self {
+            Self::UnexpectedEnd => tr("replay_format.unexpected_end").to_owned(),
+            Self::UnknownLevel(level) => {
+                tr_args("replay_format.unknown_level", &[("level", level)])
+            }
+            Self::MineOutOfBounds { row, column } => tr_args(
+                "replay_format.mine_out_of_bounds",
+                &[("row", row), ("column", column)],
+            ),
+            Self::DuplicateMine { row, column } => tr_args(
+                "replay_format.duplicate_mine",
+                &[("row", row), ("column", column)],
+            ),
+            Self::MissingField(field) => {
+                tr_args("replay_format.missing_field", &[("field", field)])
+            }
+            Self::Syntax { line, problem } => tr_args(
+                "replay_format.syntax",
+                &[("line", line), ("problem", problem)],
+            ),
+            Self::BoardTooLarge => tr("replay_format.board_too_large").to_owned(),
+            Self::StartsFromPosition => tr("replay_format.starts_from_position").to_owned(),
+            Self::UnknownFormat(name) => tr_args("replay_format.unknown_format", &[("name", name)]),
+            Self::InvalidBoard(err) => tr_args("replay_format.invalid_board", &[("error", err)]),
+        };
+        f.write_str(&message)
+    }
+}
+
+/// What is wrong with a line of a RAWVF file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SyntaxProblem {
+    /// A header value, named by its key, that should be a number.
+    NotANumber(String),
+    /// A board row whose length is not the declared width.
+    RowLength(usize),
+    MissingTime,
+    MissingColumn,
+    MissingRow,
+}
+
+impl std::fmt::Display for SyntaxProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self {
+            Self::NotANumber(key) => tr_args("replay_format.not_a_number", &[("key", key)]),
+            Self::RowLength(width) => tr_args("replay_format.row_length", &[("width", width)]),
+            Self::MissingTime => tr("replay_format.missing_time").to_owned(),
+            Self::MissingColumn => tr("replay_format.missing_column").to_owned(),
+            Self::MissingRow => tr("replay_format.missing_row").to_owned(),
+        };
+        f.write_str(&message)
+    }
+}
+
+impl std::error::Error for ReplayFormatError {}
+
+/// Community replay file formats that can be imported and exported.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReplayFormat {
+    Avf,
+    Rawvf,
+}
+
+impl ReplayFormat {
+    pub fn from_file_name(name: &str) -> Option<Self> {
+        let extension = name.rsplit_once('.')?.1.to_ascii_lowercase();
+        match extension.as_str() {
+            "avf" => Some(Self::Avf),
+            "rawvf" => Some(Self::Rawvf),
+            _ => None,
+        }
+    }
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Avf => "avf",
+            Self::Rawvf => "rawvf",
+        }
+    }
+    /// Parses a replay file, picking the format from its name.
+    ///
+    /// # Errors
+    ///
+    /// Fails when the name has no known extension, the content does not parse, or the board
+    /// is not playable.
+    pub fn import(name: &str, data: &[u8]) -> Result<Replay, ReplayFormatError> {
+        match Self::from_file_name(name) {
+            Some(Self::Avf) => avf::read(data),
+            Some(Self::Rawvf) => rawvf::read(&String::from_utf8_lossy(data)),
+            None => Err(ReplayFormatError::UnknownFormat(name.to_owned())),
+        }
+    }
+    /// Serializes a replay in this format.
+    ///
+    /// # Errors
+    ///
+    /// Fails when the board does not fit the format, or the replay starts from a loaded
+    /// position.
+    pub fn export(self, replay: &Replay) -> Result<Vec<u8>, ReplayFormatError> {
+        match self {
+            Self::Avf => avf::write(replay),
+            Self::Rawvf => rawvf::write(replay).map(String::into_bytes),
+        }
+    }
+}
+
+/// Playback state for the replay viewer.
+#[derive(Clone, Debug)]
+pub struct ReplayPlayer {
+    pub replay: Replay,
+    pub metrics: RoundMetrics,
+    pub round: RoundData,
+    pub time_ms: f64,
+    pub playing: bool,
+    pub speed: f32,
+    applied_events: usize,
+}
+
+impl ReplayPlayer {
+    pub fn new(replay: Replay) -> Self {
+        Self {
+            round: replay.initial_round(),
+            metrics: RoundMetrics::from_replay(&replay),
+            replay,
+            time_ms: 0.0,
+            playing: false,
+            speed: 1.0,
+            applied_events: 0,
+        }
+    }
+    pub fn duration_ms(&self) -> u32 {
+        self.replay.duration_ms()
+    }
+    /// Current playback position, rounded down to whole milliseconds.
+    pub fn position_ms(&self) -> u32 {
+        self.time_ms as u32
+    }
+    pub fn toggle_playing(&mut self) {
+        if !self.playing && self.position_ms() >= self.duration_ms() {
+            self.seek(0);
+        }
+        self.playing = !self.playing;
+    }
+    /// Advances playback by `dt` seconds of wall-clock time.
+    pub fn advance(&mut self, dt: f32) {
+        if !self.playing {
+            return;
+        }
+        let duration = f64::from(self.duration_ms());
+        let target = (self.time_ms + f64::from(dt * self.speed) * 1000.0).min(duration);
+        if target >= duration {
+            self.playing = false;
+        }
+        self.time_ms = target;
+        self.sync_round();
+    }
+    pub fn seek(&mut self, time_ms: u32) {
+        self.time_ms = f64::from(time_ms.min(self.duration_ms()));
+        self.sync_round();
+    }
+    /// Jumps to the next recorded action after the current position.
+    pub fn step_forward(&mut self) {
+        self.playing = false;
+        let next = self
+            .replay
+            .events
+            .get(self.applied_events)
+            .map_or(self.duration_ms(), |it| it.time_ms);
+        self.seek(next);
+    }
+    /// Jumps back to the recorded action before the last one shown.
+    pub fn step_backward(&mut self) {
+        self.playing = false;
+        let previous = self
+            .applied_events
+            .checked_sub(2)
+            .and_then(|it| self.replay.events.get(it))
+            .map_or(0, |it| it.time_ms);
+        self.seek(previous);
+    }
+    fn sync_round(&mut self) {
+        let now = self.position_ms();
+        let applied_until = self
+            .applied_events
+            .checked_sub(1)
+            .and_then(|it| self.replay.events.get(it))
+            .map(|it| it.time_ms);
+        if applied_until.is_some_and(|it| it > now) {
+            self.round = self.replay.initial_round();
+            self.applied_events = 0;
+        }
+        while self
+            .replay
+            .events
+            .get(self.applied_events)
+            .is_some_and(|it| it.time_ms <= now)
+        {
+            self.apply_next_event();
+        }
+        self.round.update_round_state();
+        self.round.time_passed = now / 1000;
+    }
+    fn apply_next_event(&mut self) {
+        if let Some(event) = self.replay.events.get(self.applied_events) {
+            self.round.apply_action(&event.action);
+            self.applied_events += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Replay, ReplayFormat, ReplayFormatError};
+
+    #[test]
+    fn only_replay_extensions_are_imported() {
+        assert_eq!(
+            ReplayFormat::from_file_name("Game.RAWVF"),
+            Some(ReplayFormat::Rawvf),
+            "extension case ignored"
+        );
+        assert!(
+            matches!(
+                ReplayFormat::import("notes.txt", b"Width: 8"),
+                Err(ReplayFormatError::UnknownFormat(_))
+            ),
+            "text files are not replays"
+        );
+    }
+
+    #[test]
+    fn replays_from_a_loaded_position_are_not_exported() {
+        let board = crate::board_format::read("1*..\n11..\n....\n").expect("valid board");
+        let replay = crate::data::RoundData::from_board_state(board).replay;
+        for format in [ReplayFormat::Avf, ReplayFormat::Rawvf] {
+            assert!(
+                matches!(
+                    format.export(&replay),
+                    Err(ReplayFormatError::StartsFromPosition)
+                ),
+                "{format:?} export would start from an empty board"
+            );
+        }
+    }
+
+    #[test]
+    fn unplayable_boards_are_rejected_before_replaying() {
+        let text = "Width: 0\nHeight: 0\nBoard:\nEvents:\n0.00 lr 1 1\n";
+        assert!(
+            matches!(
+                ReplayFormat::import("empty.rawvf", text.as_bytes()),
+                Err(ReplayFormatError::InvalidBoard(_))
+            ),
+            "empty board accepted"
+        );
+    }
+
+    #[test]
+    fn cursor_trail_is_downsampled() {
+        let mut replay = Replay::new(9, 9, vec![]);
+        // A pointer moving a cell per second, reported every frame at 1 kHz for 10 s.
+        for time_ms in 0..10_000_u32 {
+            let column = time_ms as f32 / 1000.0;
+            replay.record_cursor(time_ms, column, 4.0);
+        }
+        assert!(
+            (70..=90).contains(&replay.cursor.len()),
+            "one sample per eighth of a cell, found {}",
+            replay.cursor.len()
+        );
+        // Resting on one spot adds nothing.
+        for time_ms in 10_000..20_000_u32 {
+            replay.record_cursor(time_ms, 8.0, 4.0);
+        }
+        assert!(replay.cursor.len() <= 91, "found {}", replay.cursor.len());
+
+        // A fast flick, ten cells in a second, is capped by time instead.
+        let mut replay = Replay::new(9, 9, vec![]);
+        for time_ms in 0..1000_u32 {
+            replay.record_cursor(time_ms, time_ms as f32 / 100.0, 4.0);
+        }
+        assert!(
+            replay.cursor.len() <= 34,
+            "one sample per 30 ms, found {}",
+            replay.cursor.len()
+        );
+    }
+}
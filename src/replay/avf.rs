@@ -0,0 +1,348 @@
+//! Minesweeper Arbiter `.avf` replays.
+//!
+//! The layout read and written here, multi-byte integers big-endian:
+//!
+//! | size        | content                                                      |
+//! |-------------|--------------------------------------------------------------|
+//! | 1           | format version                                               |
+//! | 4           | reserved                                                     |
+//! | 1           | level: 3 beginner, 4 intermediate, 5 expert, 6 custom        |
+//! | 1 + 1 + 2   | custom level only: width − 1, height − 1, mine count         |
+//! | 2 per mine  | row and column of the mine, 1-based                          |
+//! | text        | `[…]` header holding the recording's timestamps             |
+//! | 8 per event | mouse event, see [`decode_event`]                            |
+//! | rest        | footer (checksum, skin, player name), ignored when reading   |
+//!
+//! Files written by this module carry no checksum footer.
+
+use crate::data::CellPos;
+use crate::replay::mouse::{self, MouseEvent, MouseEventKind};
+use crate::replay::{Replay, ReplayFormatError};
+
+const FORMAT_VERSION: u8 = 2;
+const LEVEL_BEGINNER: u8 = 3;
+const LEVEL_INTERMEDIATE: u8 = 4;
+const LEVEL_EXPERT: u8 = 5;
+const LEVEL_CUSTOM: u8 = 6;
+const EVENT_SIZE: usize = 8;
+/// Longest side [`write`] can store.
+const MAX_SIDE: usize = 255;
+
+/// Arbiter's event codes, as bit sets of the buttons involved.
+const EVENT_CODES: [(u8, MouseEventKind); 11] = [
+    (1, MouseEventKind::Move),
+    (3, MouseEventKind::LeftPress),
+    (5, MouseEventKind::LeftRelease),
+    (9, MouseEventKind::RightPress),
+    (17, MouseEventKind::RightRelease),
+    (33, MouseEventKind::MiddlePress),
+    (65, MouseEventKind::MiddleRelease),
+    (145, MouseEventKind::RightRelease),
+    (193, MouseEventKind::MiddleRelease),
+    (11, MouseEventKind::BothPress),
+    (21, MouseEventKind::LeftRelease),
+];
+
+struct Reader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl Reader<'_> {
+    fn u8(&mut self) -> Result<u8, ReplayFormatError> {
+        let value = self
+            .data
+            .get(self.offset)
+            .copied()
+            .ok_or(ReplayFormatError::UnexpectedEnd)?;
+        self.offset += 1;
+        Ok(value)
+    }
+    fn u16(&mut self) -> Result<u16, ReplayFormatError> {
+        Ok(u16::from_be_bytes([self.u8()?, self.u8()?]))
+    }
+    fn skip(&mut self, count: usize) -> Result<(), ReplayFormatError> {
+        if self.offset + count > self.data.len() {
+            return Err(ReplayFormatError::UnexpectedEnd);
+        }
+        self.offset += count;
+        Ok(())
+    }
+    fn event_at(&self, offset: usize) -> Option<MouseEvent> {
+        let bytes: [u8; EVENT_SIZE] = self
+            .data
+            .get(offset..offset + EVENT_SIZE)?
+            .try_into()
+            .ok()?;
+        decode_event(bytes)
+    }
+}
+
+/// Decodes one event record `[code, x_hi, sec_lo, x_lo, hundredths, y_hi, sec_hi, y_lo]`.
+///
+/// Arbiter's clock reads 1.00 s at the first click, so one second is taken off.
+pub fn decode_event(bytes: [u8; EVENT_SIZE]) -> Option<MouseEvent> {
+    let [code, x_hi, sec_lo, x_lo, hundredths, y_hi, sec_hi, y_lo] = bytes;
+    let kind = EVENT_CODES
+        .iter()
+        .find(|(it, _)| *it == code)
+        .map(|(_, kind)| *kind)?;
+    if hundredths >= 100 {
+        return None;
+    }
+    let seconds = i64::from(u16::from_be_bytes([sec_hi, sec_lo]));
+    Some(MouseEvent {
+        time_ms: (seconds - 1) * 1000 + i64::from(hundredths) * 10,
+        kind,
+        x: u32::from(u16::from_be_bytes([x_hi, x_lo])),
+        y: u32::from(u16::from_be_bytes([y_hi, y_lo])),
+    })
+}
+
+/// Inverse of [`decode_event`].
+pub fn encode_event(event: &MouseEvent) -> [u8; EVENT_SIZE] {
+    let code = EVENT_CODES
+        .iter()
+        .find(|(_, kind)| *kind == event.kind)
+        .map_or(1, |(code, _)| *code);
+    let clock = (event.time_ms + 1000).max(0);
+    let [sec_hi, sec_lo] = u16::try_from(clock / 1000)
+        .unwrap_or(u16::MAX)
+        .to_be_bytes();
+    let hundredths = ((clock % 1000) / 10) as u8;
+    let [x_hi, x_lo] = u16::try_from(event.x).unwrap_or(u16::MAX).to_be_bytes();
+    let [y_hi, y_lo] = u16::try_from(event.y).unwrap_or(u16::MAX).to_be_bytes();
+    [code, x_hi, sec_lo, x_lo, hundredths, y_hi, sec_hi, y_lo]
+}
+
+/// Parses an `.avf` file.
+///
+/// # Errors
+///
+/// Fails when the file is truncated, names an unknown level, places mines off the board
+/// or twice on one cell, or describes a board that is not playable.
+pub fn read(data: &[u8]) -> Result<Replay, ReplayFormatError> {
+    let mut reader = Reader { data, offset: 0 };
+    reader.u8()?;
+    reader.skip(4)?;
+    let (width, height, mine_count) = match reader.u8()? {
+        LEVEL_BEGINNER => (8, 8, 10),
+        LEVEL_INTERMEDIATE => (16, 16, 40),
+        LEVEL_EXPERT => (30, 16, 99),
+        LEVEL_CUSTOM => (
+            usize::from(reader.u8()?) + 1,
+            usize::from(reader.u8()?) + 1,
+            usize::from(reader.u16()?),
+        ),
+        level => return Err(ReplayFormatError::UnknownLevel(level)),
+    };
+
+    let mut mines = Vec::with_capacity(mine_count);
+    let mut taken = vec![false; width * height];
+    for _ in 0..mine_count {
+        let row = usize::from(reader.u8()?);
+        let column = usize::from(reader.u8()?);
+        if row == 0 || column == 0 || row > height || column > width {
+            return Err(ReplayFormatError::MineOutOfBounds { row, column });
+        }
+        if taken
+            .get_mut((row - 1) * width + column - 1)
+            .is_some_and(|it| std::mem::replace(it, true))
+        {
+            return Err(ReplayFormatError::DuplicateMine { row, column });
+        }
+        mines.push(CellPos::new(row - 1, column - 1));
+    }
+
+    // Skip the bracketed header text.
+    while reader.u8()? != b'[' {}
+    while reader.u8()? != b']' {}
+
+    // Some writers pad the header; events start at the first run of valid records.
+    while reader.offset < data.len() && !starts_event_run(&reader, reader.offset) {
+        reader.offset += 1;
+    }
+    let mut events: Vec<MouseEvent> = vec![];
+    while let Some(event) = reader.event_at(reader.offset) {
+        if events
+            .last()
+            .is_some_and(|last| last.time_ms > event.time_ms)
+        {
+            break;
+        }
+        events.push(event);
+        reader.offset += EVENT_SIZE;
+    }
+
+    mouse::to_replay(width, height, mines, &events)
+}
+
+fn starts_event_run(reader: &Reader<'_>, offset: usize) -> bool {
+    let mut previous = None;
+    for index in 0..3 {
+        match reader.event_at(offset + index * EVENT_SIZE) {
+            Some(event) => {
+                if previous.is_some_and(|it| it > event.time_ms) {
+                    return false;
+                }
+                previous = Some(event.time_ms);
+            }
+            // Fewer than three records left is fine as long as there was one.
+            None => return index > 0 && offset + index * EVENT_SIZE >= reader.data.len(),
+        }
+    }
+    true
+}
+
+/// Writes a replay as an `.avf` file.
+///
+/// # Errors
+///
+/// Fails when the board is larger than the format allows (255 × 255, 65535 mines), as a
+/// mine's 1-based row and column must each fit in a byte, or when the replay starts from
+/// a loaded position, as the file only holds the mines.
+pub fn write(replay: &Replay) -> Result<Vec<u8>, ReplayFormatError> {
+    if !replay.revealed.is_empty() || !replay.flagged.is_empty() {
+        return Err(ReplayFormatError::StartsFromPosition);
+    }
+    if replay.width > MAX_SIDE || replay.height > MAX_SIDE {
+        return Err(ReplayFormatError::BoardTooLarge);
+    }
+    let mut data = vec![FORMAT_VERSION, 0, 0, 0, 0];
+    match (replay.width, replay.height, replay.mines.len()) {
+        (8, 8, 10) => data.push(LEVEL_BEGINNER),
+        (16, 16, 40) => data.push(LEVEL_INTERMEDIATE),
+        (30, 16, 99) => data.push(LEVEL_EXPERT),
+        (width, height, mine_count) => {
+            data.push(LEVEL_CUSTOM);
+            data.push(to_byte(width.saturating_sub(1))?);
+            data.push(to_byte(height.saturating_sub(1))?);
+            data.extend(
+                u16::try_from(mine_count)
+                    .or(Err(ReplayFormatError::BoardTooLarge))?
+                    .to_be_bytes(),
+            );
+        }
+    }
+    for mine in &replay.mines {
+        data.push(to_byte(mine.x + 1)?);
+        data.push(to_byte(mine.y + 1)?);
+    }
+    let timestamp = chrono::Utc::now().timestamp_millis();
+    data.extend(format!("[0|{timestamp}|{timestamp}]").bytes());
+    for event in mouse::from_replay(replay) {
+        data.extend(encode_event(&event));
+    }
+    Ok(data)
+}
+
+fn to_byte(value: usize) -> Result<u8, ReplayFormatError> {
+    u8::try_from(value).or(Err(ReplayFormatError::BoardTooLarge))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EVENT_SIZE, decode_event, read, write};
+    use crate::action::GameAction;
+    use crate::data::CellPos;
+    use crate::replay::mouse::{MouseEvent, MouseEventKind};
+    use crate::replay::{Replay, ReplayFormatError};
+
+    const BEGINNER: &[u8] = include_bytes!("fixtures/beginner.avf");
+    /// Where the events of the fixture start and end; a footer follows them.
+    const EVENTS: std::ops::Range<usize> = 0x4e..0x4e + 7 * EVENT_SIZE;
+
+    fn event_at(offset: usize) -> Option<MouseEvent> {
+        let bytes = BEGINNER.get(offset..offset + EVENT_SIZE)?;
+        decode_event(bytes.try_into().ok()?)
+    }
+
+    #[test]
+    fn fixture_events_decode() {
+        assert_eq!(
+            event_at(EVENTS.start),
+            Some(MouseEvent {
+                time_ms: 0,
+                kind: MouseEventKind::LeftPress,
+                x: 56,
+                y: 24,
+            }),
+            "first event is the press at 1.00 s on Arbiter's clock"
+        );
+        assert_eq!(
+            event_at(EVENTS.end - EVENT_SIZE),
+            Some(MouseEvent {
+                time_ms: 1130,
+                kind: MouseEventKind::LeftRelease,
+                x: 40,
+                y: 40,
+            }),
+            "last event is the release on the mine"
+        );
+        assert_eq!(event_at(EVENTS.end), None, "the footer is no event");
+    }
+
+    #[test]
+    fn fixture_replays_its_clicks() {
+        let replay = read(BEGINNER).expect("fixture parses");
+        assert_eq!((replay.width, replay.height), (8, 8), "beginner board");
+        assert_eq!(
+            replay.mines.last(),
+            Some(&CellPos::new(7, 7)),
+            "mines are 1-based row, column"
+        );
+        let actions: Vec<(u32, GameAction)> = replay
+            .events
+            .iter()
+            .map(|it| (it.time_ms, it.action.clone()))
+            .collect();
+        assert_eq!(
+            actions,
+            [
+                (0, GameAction::Reveal(CellPos::new(1, 3))),
+                (540, GameAction::ToggleFlag(CellPos::new(0, 0))),
+                (1050, GameAction::Reveal(CellPos::new(2, 2))),
+            ],
+            "clicks become actions timed from the first reveal"
+        );
+    }
+
+    #[test]
+    fn mines_listed_twice_are_rejected() {
+        let mut data = BEGINNER.to_vec();
+        // The second mine, at row 3, column 3, becomes a copy of the first.
+        if let Some(mine) = data.get_mut(8..10) {
+            mine.copy_from_slice(&[1, 1]);
+        }
+        assert!(
+            matches!(
+                read(&data),
+                Err(ReplayFormatError::DuplicateMine { row: 1, column: 1 })
+            ),
+            "duplicate mine accepted"
+        );
+    }
+
+    #[test]
+    fn unplayable_boards_are_rejected() {
+        // Custom 1×1 board with its only cell a mine.
+        let data = [2, 0, 0, 0, 0, 6, 0, 0, 0, 1, 1, 1, b'[', b']'];
+        assert!(
+            matches!(read(&data), Err(ReplayFormatError::InvalidBoard(_))),
+            "unplayable board accepted"
+        );
+    }
+
+    #[test]
+    fn boards_wider_than_a_byte_are_refused() {
+        let replay = Replay::new(256, 8, vec![CellPos::new(0, 255)]);
+        assert!(
+            matches!(write(&replay), Err(ReplayFormatError::BoardTooLarge)),
+            "column 256 cannot be stored"
+        );
+        let replay = Replay::new(255, 8, vec![CellPos::new(0, 254)]);
+        let data = write(&replay).expect("255 columns fit");
+        let again = read(&data).expect("written replay parses");
+        assert_eq!(again.mines, replay.mines, "last column kept");
+    }
+}
@@ -0,0 +1,190 @@
+//! Low-level mouse events, the common ground of the community replay formats.
+
+use crate::action::GameAction;
+use crate::config::BoardConfig;
+use crate::data::{CellPos, CellRenderState};
+use crate::replay::{CursorSample, Replay, ReplayFormatError};
+
+/// Side length of a cell in the pixel coordinates used by Arbiter and RAWVF.
+pub const PIXELS_PER_CELL: u32 = 16;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MouseEventKind {
+    Move,
+    LeftPress,
+    LeftRelease,
+    RightPress,
+    RightRelease,
+    MiddlePress,
+    MiddleRelease,
+    /// Both buttons pressed together.
+    BothPress,
+}
+
+impl MouseEventKind {
+    /// The event's name in RAWVF files.
+    pub fn rawvf_name(self) -> &'static str {
+        match self {
+            Self::Move => "mv",
+            Self::LeftPress => "lc",
+            Self::LeftRelease => "lr",
+            Self::RightPress => "rc",
+            Self::RightRelease => "rr",
+            Self::MiddlePress => "mc",
+            Self::MiddleRelease => "mr",
+            Self::BothPress => "sc",
+        }
+    }
+    pub fn from_rawvf_name(name: &str) -> Option<Self> {
+        [
+            Self::Move,
+            Self::LeftPress,
+            Self::LeftRelease,
+            Self::RightPress,
+            Self::RightRelease,
+            Self::MiddlePress,
+            Self::MiddleRelease,
+            Self::BothPress,
+        ]
+        .into_iter()
+        .find(|it| it.rawvf_name() == name)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MouseEvent {
+    /// Milliseconds on the recording's own clock; may start before the first reveal.
+    pub time_ms: i64,
+    pub kind: MouseEventKind,
+    /// Pointer position in pixels, [`PIXELS_PER_CELL`] to a cell.
+    pub x: u32,
+    pub y: u32,
+}
+
+impl MouseEvent {
+    fn cell(&self) -> CellPos {
+        CellPos::new(
+            (self.y / PIXELS_PER_CELL) as usize,
+            (self.x / PIXELS_PER_CELL) as usize,
+        )
+    }
+}
+
+/// Replays raw mouse input against the board and keeps the actions that changed it.
+///
+/// Left release reveals, right press flags, and a release while the other button is
+/// held (or a middle release) chords, as in the classic game.
+///
+/// # Errors
+///
+/// Fails when [`BoardConfig`] rejects the board, before anything is built on it.
+pub fn to_replay(
+    width: usize,
+    height: usize,
+    mines: Vec<CellPos>,
+    events: &[MouseEvent],
+) -> Result<Replay, ReplayFormatError> {
+    BoardConfig::new(width, height, mines.len()).map_err(ReplayFormatError::InvalidBoard)?;
+    let mut replay = Replay::new(width, height, mines);
+    let mut round = replay.initial_round();
+    let mut left_down = false;
+    let mut right_down = false;
+    let mut actions = vec![];
+
+    for event in events {
+        let cell = event.cell();
+        let action = match event.kind {
+            MouseEventKind::Move | MouseEventKind::MiddlePress => None,
+            MouseEventKind::LeftPress => {
+                left_down = true;
+                None
+            }
+            MouseEventKind::RightPress => {
+                right_down = true;
+                (!left_down).then_some(GameAction::ToggleFlag(cell))
+            }
+            MouseEventKind::BothPress => {
+                left_down = true;
+                right_down = true;
+                None
+            }
+            MouseEventKind::LeftRelease => {
+                left_down = false;
+                if right_down {
+                    Some(GameAction::Chord(cell))
+                } else {
+                    let covered = round
+                        .board_data
+                        .get_cell(&cell)
+                        .is_some_and(|it| it.render_state == CellRenderState::Covered);
+                    covered.then_some(GameAction::Reveal(cell))
+                }
+            }
+            MouseEventKind::RightRelease => {
+                right_down = false;
+                left_down.then_some(GameAction::Chord(cell))
+            }
+            MouseEventKind::MiddleRelease => Some(GameAction::Chord(cell)),
+        };
+        if let Some(action) = action {
+            if round.apply_action(&action) {
+                actions.push((event.time_ms, action));
+            }
+        }
+    }
+
+    // The replay clock starts at the first action that changed the board.
+    let start = actions.first().map_or(0, |(time_ms, _)| *time_ms);
+    let to_replay_time = |time_ms: i64| u32::try_from(time_ms - start).ok();
+    for (time_ms, action) in actions {
+        replay.record(to_replay_time(time_ms).unwrap_or(0), action);
+    }
+    replay.cursor = events
+        .iter()
+        .filter(|it| it.kind == MouseEventKind::Move)
+        .filter_map(|it| {
+            Some(CursorSample {
+                time_ms: to_replay_time(it.time_ms)?,
+                column: it.x as f32 / PIXELS_PER_CELL as f32,
+                row: it.y as f32 / PIXELS_PER_CELL as f32,
+            })
+        })
+        .collect();
+    Ok(replay)
+}
+
+/// Expands a replay into the mouse presses and releases that would reproduce it.
+pub fn from_replay(replay: &Replay) -> Vec<MouseEvent> {
+    let to_pixels = |cells: f32| (cells.max(0.0) * PIXELS_PER_CELL as f32) as u32;
+    let mut events: Vec<MouseEvent> = replay
+        .cursor
+        .iter()
+        .map(|it| MouseEvent {
+            time_ms: i64::from(it.time_ms),
+            kind: MouseEventKind::Move,
+            x: to_pixels(it.column),
+            y: to_pixels(it.row),
+        })
+        .collect();
+    for event in &replay.events {
+        let click = match event.action {
+            GameAction::Reveal(_) => [MouseEventKind::LeftPress, MouseEventKind::LeftRelease],
+            GameAction::ToggleFlag(_) => [MouseEventKind::RightPress, MouseEventKind::RightRelease],
+            GameAction::Chord(_) => [MouseEventKind::MiddlePress, MouseEventKind::MiddleRelease],
+        };
+        let pos = event.action.position();
+        let x = pos.y as u32 * PIXELS_PER_CELL + PIXELS_PER_CELL / 2;
+        let y = pos.x as u32 * PIXELS_PER_CELL + PIXELS_PER_CELL / 2;
+        for kind in click {
+            events.push(MouseEvent {
+                time_ms: i64::from(event.time_ms),
+                kind,
+                x,
+                y,
+            });
+        }
+    }
+    // Stable sort keeps each press before its release and moves before clicks.
+    events.sort_by_key(|it| it.time_ms);
+    events
+}
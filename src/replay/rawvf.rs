@@ -0,0 +1,308 @@
+//! RAWVF, the community's plain-text replay format.
+//!
+//! A file is a list of `Key: Value` header lines, then a `Board:` section with one line
+//! per row (`*` for a mine, anything else for a safe cell), then an `Events:` section:
+//!
+//! ```text
+//! RawVF_Version: Rev5
+//! Width: 8
+//! Height: 8
+//! Mines: 10
+//! Board:
+//! *0000000
+//! …
+//! Events:
+//! 0.00 start
+//! 0.00 lc 2 1 (24 8)
+//! 0.05 lr 2 1 (24 8)
+//! 3.21 won
+//! ```
+//!
+//! Event lines are `seconds kind column row`, with the cell 1-based and an optional
+//! `(x y)` suffix giving the pointer in pixels. Status lines such as `start`, `won` or
+//! `blast` are ignored when reading.
+
+use crate::data::CellPos;
+use crate::metrics::RoundMetrics;
+use crate::replay::mouse::{self, MouseEvent, MouseEventKind, PIXELS_PER_CELL};
+use crate::replay::{Replay, ReplayFormatError, SyntaxProblem};
+
+/// Parses a RAWVF file.
+///
+/// # Errors
+///
+/// Fails on a missing or malformed header value, a board that does not match the declared
+/// size, or an unreadable event line. Errors carry the 1-based line number.
+pub fn read(text: &str) -> Result<Replay, ReplayFormatError> {
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()));
+    let mut width = None;
+    let mut height = None;
+
+    for (number, line) in lines.by_ref() {
+        if line == "Board:" {
+            break;
+        }
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let parse = || {
+            value
+                .trim()
+                .parse::<usize>()
+                .or(Err(ReplayFormatError::Syntax {
+                    line: number,
+                    problem: SyntaxProblem::NotANumber(key.trim().to_owned()),
+                }))
+        };
+        match key.trim() {
+            "Width" => width = Some(parse()?),
+            "Height" => height = Some(parse()?),
+            _ => {}
+        }
+    }
+    let width = width.ok_or(ReplayFormatError::MissingField("Width"))?;
+    let height = height.ok_or(ReplayFormatError::MissingField("Height"))?;
+
+    let mut mines = vec![];
+    for row in 0..height {
+        let (number, line) = lines.next().ok_or(ReplayFormatError::UnexpectedEnd)?;
+        if line.chars().count() != width {
+            return Err(ReplayFormatError::Syntax {
+                line: number,
+                problem: SyntaxProblem::RowLength(width),
+            });
+        }
+        mines.extend(
+            line.chars()
+                .enumerate()
+                .filter(|(_, it)| *it == '*')
+                .map(|(column, _)| CellPos::new(row, column)),
+        );
+    }
+
+    let mut events = vec![];
+    let mut in_events = false;
+    for (number, line) in lines {
+        if line.is_empty() {
+            continue;
+        }
+        if !in_events {
+            in_events = line == "Events:";
+            continue;
+        }
+        if let Some(event) = parse_event(number, line)? {
+            events.push(event);
+        }
+    }
+    mouse::to_replay(width, height, mines, &events)
+}
+
+fn parse_event(number: usize, line: &str) -> Result<Option<MouseEvent>, ReplayFormatError> {
+    let syntax = |problem| ReplayFormatError::Syntax {
+        line: number,
+        problem,
+    };
+    let mut fields = line.split_whitespace();
+    let seconds: f64 = fields
+        .next()
+        .and_then(|it| it.parse().ok())
+        .ok_or_else(|| syntax(SyntaxProblem::MissingTime))?;
+    let Some(kind) = fields.next().and_then(MouseEventKind::from_rawvf_name) else {
+        // Status lines such as `start`, `won` or `blast`.
+        return Ok(None);
+    };
+    let mut number = || fields.next()?.trim_matches(['(', ')']).parse::<u32>().ok();
+    let column = number().ok_or_else(|| syntax(SyntaxProblem::MissingColumn))?;
+    let row = number().ok_or_else(|| syntax(SyntaxProblem::MissingRow))?;
+    let pixels = number().zip(number());
+    let (Some(left), Some(top)) = (
+        column.checked_sub(1).map(|it| it * PIXELS_PER_CELL),
+        row.checked_sub(1).map(|it| it * PIXELS_PER_CELL),
+    ) else {
+        // Off the board, left of or above it.
+        return Ok(None);
+    };
+    // The pixels place the pointer within the cell, unless they were measured on another
+    // scale and land elsewhere.
+    let within = |pixel: u32, start: u32| (start..start + PIXELS_PER_CELL).contains(&pixel);
+    let (x, y) = match pixels {
+        Some((x, y)) if within(x, left) && within(y, top) => (x, y),
+        _ => (left + PIXELS_PER_CELL / 2, top + PIXELS_PER_CELL / 2),
+    };
+    Ok(Some(MouseEvent {
+        time_ms: (seconds * 1000.0).round() as i64,
+        kind,
+        x,
+        y,
+    }))
+}
+
+/// Writes a replay as RAWVF text.
+///
+/// # Errors
+///
+/// Fails when the replay starts from a loaded position, as the board section only holds
+/// the mines.
+pub fn write(replay: &Replay) -> Result<String, ReplayFormatError> {
+    if !replay.revealed.is_empty() || !replay.flagged.is_empty() {
+        return Err(ReplayFormatError::StartsFromPosition);
+    }
+    let metrics = RoundMetrics::from_replay(replay);
+    let level = match (replay.width, replay.height, replay.mines.len()) {
+        (8, 8, 10) => "Beginner",
+        (16, 16, 40) => "Intermediate",
+        (30, 16, 99) => "Expert",
+        _ => "Custom",
+    };
+    let mut text = format!(
+        "RawVF_Version: Rev5\n\
+         Program: {}\n\
+         Version: {}\n\
+         Level: {level}\n\
+         Width: {}\n\
+         Height: {}\n\
+         Mines: {}\n\
+         Marks: Off\n\
+         Time: {:.2}\n\
+         BBBV: {}\n\
+         Board:\n",
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION"),
+        replay.width,
+        replay.height,
+        replay.mines.len(),
+        f64::from(metrics.time_ms) / 1000.0,
+        metrics.bbbv,
+    );
+
+    let mut board = vec![vec!['0'; replay.width]; replay.height];
+    for mine in &replay.mines {
+        if let Some(cell) = board.get_mut(mine.x).and_then(|row| row.get_mut(mine.y)) {
+            *cell = '*';
+        }
+    }
+    for row in board {
+        text.extend(row);
+        text.push('\n');
+    }
+
+    text.push_str("Events:\n0.00 start\n");
+    for event in mouse::from_replay(replay) {
+        text.push_str(&format!(
+            "{:.2} {} {} {} ({} {})\n",
+            event.time_ms as f64 / 1000.0,
+            event.kind.rawvf_name(),
+            event.x / PIXELS_PER_CELL + 1,
+            event.y / PIXELS_PER_CELL + 1,
+            event.x,
+            event.y,
+        ));
+    }
+    let ending = if metrics.won { "won" } else { "blast" };
+    text.push_str(&format!(
+        "{:.2} {ending}\n",
+        f64::from(metrics.time_ms) / 1000.0
+    ));
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_event, read, write};
+    use crate::action::GameAction;
+    use crate::data::CellPos;
+    use crate::replay::mouse::{MouseEvent, MouseEventKind};
+    use crate::replay::{ReplayFormatError, SyntaxProblem};
+
+    const BEGINNER: &str = include_str!("fixtures/beginner.rawvf");
+
+    #[test]
+    fn events_are_read_cells_first() {
+        let events: Vec<MouseEvent> = BEGINNER
+            .lines()
+            .skip_while(|it| *it != "Events:")
+            .skip(1)
+            .filter_map(|line| parse_event(0, line).expect("fixture events parse"))
+            .collect();
+        assert_eq!(
+            events.first(),
+            Some(&MouseEvent {
+                time_ms: 0,
+                kind: MouseEventKind::LeftPress,
+                x: 56,
+                y: 24,
+            }),
+            "first event is the press on column 4, row 2"
+        );
+        assert_eq!(
+            events.last(),
+            Some(&MouseEvent {
+                time_ms: 1130,
+                kind: MouseEventKind::LeftRelease,
+                x: 40,
+                y: 40,
+            }),
+            "last event is the release on the mine"
+        );
+    }
+
+    #[test]
+    fn fixture_replays_its_clicks() {
+        let replay = read(BEGINNER).expect("fixture parses");
+        assert_eq!((replay.width, replay.height), (8, 8), "beginner board");
+        assert_eq!(replay.mines.len(), 10, "beginner mines");
+        assert_eq!(
+            replay.mines.first(),
+            Some(&CellPos::new(0, 0)),
+            "mines read row by row"
+        );
+        let actions: Vec<(u32, GameAction)> = replay
+            .events
+            .iter()
+            .map(|it| (it.time_ms, it.action.clone()))
+            .collect();
+        assert_eq!(
+            actions,
+            [
+                (0, GameAction::Reveal(CellPos::new(1, 3))),
+                (540, GameAction::ToggleFlag(CellPos::new(0, 0))),
+                (1050, GameAction::Reveal(CellPos::new(2, 2))),
+            ],
+            "clicks become actions timed from the first reveal"
+        );
+    }
+
+    #[test]
+    fn pixels_outside_the_cell_fall_back_to_its_centre() {
+        let event = parse_event(1, "0.50 lr 2 3 (400 400)").expect("line parses");
+        assert_eq!(
+            event.map(|it| (it.x, it.y)),
+            Some((24, 40)),
+            "centre of column 2, row 3"
+        );
+        let event = parse_event(1, "0.50 mv 0 3 (0 40)").expect("line parses");
+        assert_eq!(event, None, "off the board");
+        assert!(
+            matches!(
+                parse_event(7, "0.50 lr 2"),
+                Err(ReplayFormatError::Syntax {
+                    line: 7,
+                    problem: SyntaxProblem::MissingRow
+                })
+            ),
+            "row missing"
+        );
+    }
+
+    #[test]
+    fn written_replays_read_back() {
+        let replay = read(BEGINNER).expect("fixture parses");
+        let text = write(&replay).expect("replay from an empty board");
+        let again = read(&text).expect("written replay parses");
+        assert_eq!(again.mines, replay.mines, "same mines");
+        assert_eq!(again.events, replay.events, "same actions");
+    }
+}
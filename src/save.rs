@@ -0,0 +1,291 @@
+//! Versioned save of the round in progress, so it can be resumed after a restart.
+//!
+//! | version | stored as                                                               |
+//! |---------|-------------------------------------------------------------------------|
+//! | 0       | the whole app state under [`eframe::APP_KEY`], before rounds had a replay |
+//! | 1       | [`SavedRound`] under [`SAVE_KEY`], and in [`slots`]                        |
+//! | 2       | the same, with the board [`packed`] into maps of its mines, reveals and flags |
+//!
+//! Older versions are migrated on load. A save that cannot be read is logged and
+//! skipped, so the app starts on a fresh round instead.
+
+mod packed;
+pub mod slots;
+
+use crate::config::BoardConfig;
+use crate::data::{RoundData, RoundState};
+use crate::i18n::{tr, tr_args};
+
+pub const SAVE_KEY: &str = "round";
+pub const SAVE_VERSION: u32 = 2;
+/// Extension of a [`SavedRound`] exported to its own file.
+pub const SAVE_FILE_EXTENSION: &str = "msave";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SaveError {
+    Corrupt(String),
+    /// A save slot listed without its save.
+    MissingSlot,
+    /// Written by a newer build of the game.
+    UnsupportedVersion(u32),
+}
+
+impl std::fmt::Display for SaveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Corrupt(message) => {
+                f.write_str(&tr_args("save.corrupt", &[("message", message)]))
+            }
+            Self::MissingSlot => f.write_str(tr("save.missing_slot")),
+            Self::UnsupportedVersion(version) => f.write_str(&tr_args(
+                "save.unsupported_version",
+                &[("version", version), ("supported", &SAVE_VERSION)],
+            )),
+        }
+    }
+}
+
+impl std::error::Error for SaveError {}
+
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug)]
+pub struct SavedRound {
+    pub version: u32,
+    /// Time on the round's clock when saved; the wall clock keeps running while the app
+    /// is closed.
+    pub elapsed_ms: u32,
+    #[serde(with = "packed")]
+    pub round: RoundData,
+}
+
+impl SavedRound {
+    pub fn new(round: &RoundData) -> Self {
+        let elapsed_ms = match round.round_state_type {
+            RoundState::Playing => round.elapsed_ms(),
+            RoundState::NotStarted | RoundState::Ended(_) => round.time_passed * 1000,
+        };
+        Self {
+            version: SAVE_VERSION,
+            elapsed_ms,
+            round: round.clone(),
+        }
+    }
+
+    /// Restarts the round's clock where it stopped.
+    pub fn resume(self) -> RoundData {
+        let mut round = self.round;
+        if round.round_state_type != RoundState::NotStarted {
+            round.start_time = chrono::Utc::now().timestamp_millis() - i64::from(self.elapsed_ms);
+            round.time_passed = self.elapsed_ms / 1000;
+        }
+        round
+    }
+}
+
+/// Just enough of a save to pick the right migration.
+#[derive(serde::Deserialize)]
+struct SaveHeader {
+    #[serde(default)]
+    version: u32,
+}
+
+pub fn save(storage: &mut dyn eframe::Storage, round: &RoundData) {
+    eframe::set_value(storage, SAVE_KEY, &SavedRound::new(round));
+}
+
+/// Reads the saved round, migrating older versions. `Ok(None)` means nothing was saved.
+///
+/// # Errors
+///
+/// Fails when the save cannot be decoded, holds an unplayable board, or comes from a newer
+/// version of the game.
+pub fn load(storage: &dyn eframe::Storage) -> Result<Option<RoundData>, SaveError> {
+    if let Some(text) = storage.get_string(SAVE_KEY) {
+        return decode(&text).map(|it| Some(it.resume()));
+    }
+    match storage.get_string(eframe::APP_KEY) {
+        Some(text) => v0::migrate(&text).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Reads a [`SavedRound`].
+///
+/// # Errors
+///
+/// Fails when the text cannot be decoded, holds an unplayable board, or comes from a newer
+/// version of the game.
+pub fn decode(text: &str) -> Result<SavedRound, SaveError> {
+    let header: SaveHeader = parse(text)?;
+    let saved = match header.version {
+        1 => v1::migrate(text)?,
+        SAVE_VERSION => parse(text)?,
+        version => return Err(SaveError::UnsupportedVersion(version)),
+    };
+    check_board(&saved.round)?;
+    Ok(saved)
+}
+
+/// Rejects a board that [`BoardConfig`] would not let the player start.
+fn check_board(round: &RoundData) -> Result<(), SaveError> {
+    BoardConfig::new(round.board_width, round.board_height, round.total_mine)
+        .map(|_| ())
+        .map_err(|err| SaveError::Corrupt(err.to_string()))
+}
+
+fn parse<T: serde::de::DeserializeOwned>(text: &str) -> Result<T, SaveError> {
+    ron::from_str(text).map_err(|err| SaveError::Corrupt(err.to_string()))
+}
+
+/// The baseline app state, which saved the round without a version or a replay.
+mod v0 {
+    use crate::data::{GameBoardData, RoundState};
+
+    #[derive(serde::Deserialize)]
+    struct AppState {
+        round: RoundData,
+    }
+
+    #[derive(serde::Deserialize)]
+    struct RoundData {
+        board_data: GameBoardData,
+        round_state_type: RoundState,
+    }
+
+    pub(super) fn migrate(text: &str) -> Result<crate::data::RoundData, super::SaveError> {
+        let state: AppState = super::parse(text)?;
+        let mut round = crate::data::RoundData::from_board_state(state.round.board_data);
+        super::check_board(&round)?;
+        round.round_state_type = state.round.round_state_type;
+        // The clock and the moves so far were not saved, so the round resumes from zero and
+        // cannot count towards the statistics.
+        round.recorded = true;
+        round.update_round_state();
+        Ok(round)
+    }
+}
+
+/// Saves with one record per cell, before boards were packed.
+mod v1 {
+    use crate::data::RoundData;
+
+    #[derive(serde::Deserialize)]
+    struct SavedRound {
+        elapsed_ms: u32,
+        round: RoundData,
+    }
+
+    pub(super) fn migrate(text: &str) -> Result<super::SavedRound, super::SaveError> {
+        let saved: SavedRound = super::parse(text)?;
+        Ok(super::SavedRound {
+            version: super::SAVE_VERSION,
+            elapsed_ms: saved.elapsed_ms,
+            round: saved.round,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SAVE_VERSION, SaveError, SavedRound, decode};
+    use crate::action::GameAction;
+    use crate::config::BoardConfig;
+    use crate::data::{CellPos, GameBoardData, RoundData, RoundState};
+
+    fn played() -> RoundData {
+        let board = crate::board_format::read("*.....\n......\n...*..\n.....*\n").expect("valid");
+        let mut round = RoundData::from_board(3, board);
+        round.perform(GameAction::Reveal(CellPos::new(0, 5)));
+        round.perform(GameAction::ToggleFlag(CellPos::new(0, 0)));
+        round.replay.record_cursor(40, 1.5, 2.5);
+        round
+    }
+
+    fn save(round: &RoundData) -> String {
+        ron::to_string(&SavedRound::new(round)).expect("serializable")
+    }
+
+    #[test]
+    fn packed_round_trips() {
+        let round = played();
+        let loaded = decode(&save(&round)).expect("readable").round;
+        assert_eq!(
+            crate::board_format::write(&loaded.board_data, true),
+            crate::board_format::write(&round.board_data, true),
+            "same board"
+        );
+        assert_eq!(loaded.replay, round.replay, "same replay");
+        assert_eq!(
+            loaded.round_state_type, round.round_state_type,
+            "same state"
+        );
+        assert_eq!(loaded.mines_remaining, 2, "flags counted");
+    }
+
+    #[test]
+    fn version_1_saves_migrate() {
+        #[derive(serde::Serialize)]
+        struct SavedRound<'a> {
+            version: u32,
+            elapsed_ms: u32,
+            round: &'a RoundData,
+        }
+        let round = played();
+        let text = ron::to_string(&SavedRound {
+            version: 1,
+            elapsed_ms: 3000,
+            round: &round,
+        })
+        .expect("serializable");
+        let saved = decode(&text).expect("migrated");
+        assert_eq!(saved.version, SAVE_VERSION, "current version");
+        assert_eq!(saved.elapsed_ms, 3000, "clock kept");
+        assert_eq!(saved.round.replay, round.replay, "replay kept");
+    }
+
+    #[test]
+    fn version_0_saves_with_unplayable_boards_are_corrupt() {
+        #[derive(serde::Serialize)]
+        struct AppState {
+            round: Round,
+        }
+        #[derive(serde::Serialize)]
+        struct Round {
+            board_data: GameBoardData,
+            round_state_type: RoundState,
+        }
+        let text = |board_data| {
+            ron::to_string(&AppState {
+                round: Round {
+                    board_data,
+                    round_state_type: RoundState::Playing,
+                },
+            })
+            .expect("serializable")
+        };
+        let mines: Vec<CellPos> = (0..4).map(|it| CellPos::new(it / 2, it % 2)).collect();
+        let full = crate::game_logic::board_from_mines(2, 2, &mines);
+        assert!(
+            matches!(super::v0::migrate(&text(full)), Err(SaveError::Corrupt(_))),
+            "board without a safe cell accepted"
+        );
+        let played = played().board_data;
+        let round = super::v0::migrate(&text(played)).expect("migrated");
+        assert_eq!(round.total_mine, 3, "mines kept");
+    }
+
+    #[test]
+    fn largest_board_stays_small() {
+        let side = crate::config::MAX_SIDE;
+        let config = BoardConfig::new(side, side, side * side / 5).expect("valid board");
+        let mut round = RoundData::new(config);
+        round.perform(GameAction::Reveal(CellPos::new(side / 2, side / 2)));
+        let text = save(&round);
+        assert!(text.len() < 200_000, "save takes {} bytes", text.len());
+        let loaded = decode(&text).expect("readable").round;
+        assert_eq!(
+            loaded.board_data.get_mine_positions(),
+            round.board_data.get_mine_positions(),
+            "same mines"
+        );
+    }
+}
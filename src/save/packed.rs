@@ -0,0 +1,128 @@
+//! The form a round takes inside a save from version 2: its board as maps of the mines,
+//! reveals and flags, packed like the mine map of a board link, instead of one record per
+//! cell. A 500×500 board then takes tens of kilobytes rather than tens of megabytes.
+//!
+//! Used through `#[serde(with = "packed")]` on [`super::SavedRound::round`].
+
+use serde::{Deserialize as _, Serialize as _};
+
+use crate::config::BoardConfig;
+use crate::data::{CellData, CellPos, CellRenderState, RoundData, RoundState};
+use crate::replay::{CursorSample, Replay, ReplayEvent};
+use crate::share::{decode_cell_map, encode_cell_map};
+
+#[derive(serde::Deserialize, serde::Serialize)]
+struct PackedRound {
+    width: usize,
+    height: usize,
+    mines: String,
+    revealed: String,
+    flagged: String,
+    round_state_type: RoundState,
+    start_time: i64,
+    /// The replay, whose mines are the board's.
+    events: Vec<ReplayEvent>,
+    cursor: Vec<CursorSample>,
+    /// Reveals and flags the replay starts from, for a round loaded from a position.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    replay_revealed: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    replay_flagged: Option<String>,
+    recorded: bool,
+    assisted: bool,
+    fixed_layout: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    slot: Option<String>,
+}
+
+pub fn serialize<S: serde::Serializer>(
+    round: &RoundData,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let (width, height) = (round.board_width, round.board_height);
+    let cells = |keep: fn(&CellData) -> bool| -> String {
+        let cells: Vec<CellPos> = round
+            .board_data
+            .cells
+            .iter()
+            .flatten()
+            .filter(|it| keep(it))
+            .map(|it| it.position.clone())
+            .collect();
+        encode_cell_map(width, height, &cells)
+    };
+    let replay = &round.replay;
+    let starting =
+        |cells: &[CellPos]| (!cells.is_empty()).then(|| encode_cell_map(width, height, cells));
+    PackedRound {
+        width,
+        height,
+        mines: cells(|it| it.is_mine),
+        revealed: cells(|it| it.render_state == CellRenderState::Revealed),
+        flagged: cells(|it| it.is_flagged),
+        round_state_type: round.round_state_type.clone(),
+        start_time: round.start_time,
+        events: replay.events.clone(),
+        cursor: replay.cursor.clone(),
+        replay_revealed: starting(&replay.revealed),
+        replay_flagged: starting(&replay.flagged),
+        recorded: round.recorded,
+        assisted: round.assisted,
+        fixed_layout: round.fixed_layout,
+        slot: round.slot.clone(),
+    }
+    .serialize(serializer)
+}
+
+pub fn deserialize<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<RoundData, D::Error> {
+    use serde::de::Error as _;
+    let packed = PackedRound::deserialize(deserializer)?;
+    let (width, height) = (packed.width, packed.height);
+    let cells = |map: &str| {
+        decode_cell_map(width, height, map).ok_or_else(|| {
+            D::Error::custom(format!("cell map does not fit a {width}×{height} board"))
+        })
+    };
+    let mines = cells(&packed.mines)?;
+    BoardConfig::new(width, height, mines.len()).map_err(D::Error::custom)?;
+
+    let mut board = crate::game_logic::board_from_mines(width, height, &mines);
+    for pos in cells(&packed.revealed)? {
+        if let Some(cell) = board.get_cell_mut(&pos) {
+            cell.render_state = CellRenderState::Revealed;
+        }
+    }
+    for pos in cells(&packed.flagged)? {
+        if let Some(cell) = board.get_cell_mut(&pos) {
+            cell.is_flagged = true;
+        }
+    }
+    let mut round = RoundData::from_board(mines.len(), board);
+    round.replay = Replay {
+        revealed: packed
+            .replay_revealed
+            .as_deref()
+            .map(cells)
+            .transpose()?
+            .unwrap_or_default(),
+        flagged: packed
+            .replay_flagged
+            .as_deref()
+            .map(cells)
+            .transpose()?
+            .unwrap_or_default(),
+        events: packed.events,
+        cursor: packed.cursor,
+        ..Replay::new(width, height, mines)
+    };
+    round.round_state_type = packed.round_state_type;
+    round.start_time = packed.start_time;
+    round.recorded = packed.recorded;
+    round.assisted = packed.assisted;
+    round.fixed_layout = packed.fixed_layout;
+    round.slot = packed.slot;
+    round.update_round_state();
+    Ok(round)
+}
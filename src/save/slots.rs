@@ -0,0 +1,159 @@
+//! Named save slots next to the automatic resume save.
+//!
+//! The slot names are listed under [`SLOTS_KEY`] and each slot is a [`SavedRound`] under
+//! its own key, so one unreadable slot does not take the others with it.
+
+use crate::data::RoundData;
+use crate::save::{SaveError, SavedRound};
+
+pub const SLOTS_KEY: &str = "slots";
+
+fn slot_key(name: &str) -> String {
+    format!("slot:{name}")
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug)]
+struct SlotEntry {
+    name: String,
+    /// Unix timestamp in milliseconds.
+    saved_at: i64,
+}
+
+#[derive(Clone, Debug)]
+pub struct SaveSlot {
+    pub name: String,
+    pub saved_at: i64,
+    pub round: Result<SavedRound, SaveError>,
+}
+
+impl SaveSlot {
+    pub fn date(&self) -> String {
+        chrono::DateTime::from_timestamp_millis(self.saved_at).map_or_else(String::new, |it| {
+            it.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+    }
+}
+
+/// The slots read from storage, written back whenever one changes.
+#[derive(Clone, Debug, Default)]
+pub struct SaveSlots {
+    slots: Vec<SaveSlot>,
+}
+
+impl SaveSlots {
+    pub fn load(storage: &dyn eframe::Storage) -> Self {
+        let entries: Vec<SlotEntry> = eframe::get_value(storage, SLOTS_KEY).unwrap_or_default();
+        let slots = entries
+            .into_iter()
+            .map(|entry| {
+                let round = storage
+                    .get_string(&slot_key(&entry.name))
+                    .ok_or(SaveError::MissingSlot)
+                    .and_then(|text| crate::save::decode(&text));
+                SaveSlot {
+                    name: entry.name,
+                    saved_at: entry.saved_at,
+                    round,
+                }
+            })
+            .collect();
+        Self { slots }
+    }
+
+    /// Most recently saved first.
+    pub fn slots(&self) -> &[SaveSlot] {
+        &self.slots
+    }
+
+    pub fn get(&self, name: &str) -> Option<&SaveSlot> {
+        self.slots.iter().find(|it| it.name == name)
+    }
+
+    /// Saves the round under `name`, replacing a slot of the same name.
+    pub fn save(&mut self, storage: &mut dyn eframe::Storage, name: &str, round: &RoundData) {
+        let saved = SavedRound::new(round);
+        eframe::set_value(storage, &slot_key(name), &saved);
+        self.slots.retain(|it| it.name != name);
+        self.slots.insert(
+            0,
+            SaveSlot {
+                name: name.to_owned(),
+                saved_at: chrono::Utc::now().timestamp_millis(),
+                round: Ok(saved),
+            },
+        );
+        self.write_index(storage);
+    }
+
+    /// Marks the round in the slot as counted in the statistics, after a copy loaded from
+    /// it was.
+    pub fn mark_recorded(&mut self, storage: &mut dyn eframe::Storage, name: &str) {
+        let Some(Ok(saved)) = self
+            .slots
+            .iter_mut()
+            .find(|it| it.name == name)
+            .map(|it| &mut it.round)
+        else {
+            return;
+        };
+        saved.round.recorded = true;
+        eframe::set_value(storage, &slot_key(name), saved);
+        storage.flush();
+    }
+
+    pub fn delete(&mut self, storage: &mut dyn eframe::Storage, name: &str) {
+        // Storage has no removal, so the slot is emptied and dropped from the index.
+        storage.set_string(&slot_key(name), String::new());
+        self.slots.retain(|it| it.name != name);
+        self.write_index(storage);
+    }
+
+    fn write_index(&self, storage: &mut dyn eframe::Storage) {
+        let entries: Vec<SlotEntry> = self
+            .slots
+            .iter()
+            .map(|it| SlotEntry {
+                name: it.name.clone(),
+                saved_at: it.saved_at,
+            })
+            .collect();
+        eframe::set_value(storage, SLOTS_KEY, &entries);
+        storage.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::SaveSlots;
+    use crate::data::RoundData;
+
+    #[derive(Default)]
+    struct MemoryStorage(BTreeMap<String, String>);
+
+    impl eframe::Storage for MemoryStorage {
+        fn get_string(&self, key: &str) -> Option<String> {
+            self.0.get(key).cloned()
+        }
+        fn set_string(&mut self, key: &str, value: String) {
+            self.0.insert(key.to_owned(), value);
+        }
+        fn flush(&mut self) {}
+    }
+
+    #[test]
+    fn recorded_slot_stays_recorded_after_reload() {
+        let mut storage = MemoryStorage::default();
+        let mut slots = SaveSlots::load(&storage);
+        slots.save(&mut storage, "before guess", &RoundData::default());
+        slots.mark_recorded(&mut storage, "before guess");
+
+        let slots = SaveSlots::load(&storage);
+        let slot = slots.get("before guess").expect("slot listed");
+        let saved = slot.round.as_ref().expect("slot readable");
+        assert!(saved.round.recorded, "finish counted once");
+    }
+}
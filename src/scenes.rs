@@ -1,10 +1,46 @@
 pub mod game;
+pub mod leaderboard;
+pub mod replay;
+pub mod save_slots;
+pub mod setting;
+pub mod statistics;
 pub use game::GameScene;
+pub use leaderboard::LeaderboardScene;
+pub use replay::ReplayScene;
+pub use save_slots::SaveSlotsScene;
+pub use setting::SettingScene;
+pub use statistics::StatisticsScene;
 
-#[derive(serde::Deserialize, serde::Serialize, Clone, Debug)]
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq, Eq)]
 pub enum SceneType {
     Game,
     Setting,
     RoomSetting,
     PlayerStanding,
-}
\ No newline at end of file
+    Replay,
+    Statistics,
+    SaveSlots,
+}
+
+/// Lays out a scene that fills `rect` as a page: a margin, then the heading and what
+/// `add_contents` adds, one row under another.
+fn page(
+    ui: &mut egui::Ui,
+    rect: egui::Rect,
+    heading: &str,
+    add_contents: impl FnOnce(&mut egui::Ui),
+) -> egui::Response {
+    const MARGIN: i8 = 12;
+    egui::Frame::NONE
+        .inner_margin(egui::Margin::same(MARGIN))
+        .show(ui, |ui| {
+            // Rows from the top, rather than centred and stretched like the scene itself.
+            ui.with_layout(egui::Layout::top_down(egui::Align::Min), |ui| {
+                ui.set_min_size(rect.size() - egui::Vec2::splat(2.0 * f32::from(MARGIN)));
+                ui.spacing_mut().item_spacing = egui::vec2(16.0, 6.0);
+                ui.heading(heading);
+                add_contents(ui);
+            });
+        })
+        .response
+}
@@ -1,7 +1,8 @@
 use egui::Widget;
 
 use crate::{
-    data::RoundData,
+    bindings::InputBindings,
+    data::{GlobalState, RoundData, RoundState},
     widgets::{GameBoard, GameStatusBar},
 };
 pub struct GameScene<'a> {
@@ -16,9 +17,14 @@ impl<'a> GameScene<'a> {
 
 impl Widget for GameScene<'_> {
     fn ui(self, ui: &mut egui::Ui) -> egui::Response {
-        let global_state: crate::data::GlobalState =
-            ui.data(|d| d.get_temp(egui::Id::NULL)).unwrap();
         self.round_state.update_round_state();
+        // Nothing moves on its own but the timer, so wake up only when it ticks.
+        if self.round_state.round_state_type == RoundState::Playing && !self.round_state.is_paused()
+        {
+            let until_tick = 1000 - u64::from(self.round_state.elapsed_ms() % 1000);
+            ui.ctx()
+                .request_repaint_after(std::time::Duration::from_millis(until_tick));
+        }
 
         let status_bar_rect = egui::Rect::from_min_size(
             self.rect.min,
@@ -30,15 +36,36 @@ impl Widget for GameScene<'_> {
                 self.rect.min.x,
                 self.rect.min.y + (self.rect.height() - self.rect.width()),
             ),
-            self.rect.size(),
+            egui::vec2(self.rect.width(), self.rect.width()),
         );
 
+        let reveal_button = ui
+            .data(|d| d.get_temp::<InputBindings>(egui::Id::new("INPUT_BINDINGS")))
+            .unwrap_or_default()
+            .reveal_button();
+        let cell_pressed = ui.input(|i| {
+            reveal_button.is_some_and(|it| i.pointer.button_down(it))
+                && i.pointer
+                    .press_origin()
+                    .is_some_and(|it| game_board_rect.contains(it))
+        });
+
         egui::Frame::NONE
             .show(ui, |ui| {
+                let mut restart = false;
                 ui.put(
                     status_bar_rect,
-                    GameStatusBar::new(self.round_state, status_bar_rect),
+                    GameStatusBar::new(self.round_state, status_bar_rect)
+                        .cell_pressed(cell_pressed)
+                        .restart_button(&mut restart),
                 );
+                if restart {
+                    let settings = ui
+                        .data(|d| d.get_temp::<GlobalState>(egui::Id::NULL))
+                        .map(|it| it.game_settings)
+                        .unwrap_or_default();
+                    *self.round_state = settings.new_round();
+                }
                 ui.put(
                     game_board_rect,
                     GameBoard::new(self.round_state, game_board_rect),
@@ -0,0 +1,101 @@
+use egui::Widget;
+
+use crate::i18n::{tr, tr_args};
+use crate::leaderboard::Leaderboard;
+use crate::replay::Replay;
+use crate::statistics::Difficulty;
+
+/// Best times per difficulty; picking an entry's replay fills `watch`.
+pub struct LeaderboardScene<'a> {
+    rect: egui::Rect,
+    leaderboard: &'a mut Leaderboard,
+    /// Shown until another difficulty is picked.
+    current_difficulty: Difficulty,
+    watch: &'a mut Option<Replay>,
+}
+impl<'a> LeaderboardScene<'a> {
+    pub fn new(
+        rect: egui::Rect,
+        leaderboard: &'a mut Leaderboard,
+        current_difficulty: Difficulty,
+        watch: &'a mut Option<Replay>,
+    ) -> Self {
+        Self {
+            rect,
+            leaderboard,
+            current_difficulty,
+            watch,
+        }
+    }
+}
+
+impl Widget for LeaderboardScene<'_> {
+    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+        let selected_id = egui::Id::new("LEADERBOARD_DIFFICULTY");
+        let mut selected: Difficulty = ui
+            .data(|d| d.get_temp(selected_id))
+            .unwrap_or(self.current_difficulty);
+
+        let response = super::page(ui, self.rect, tr("leaderboard.heading"), |ui| {
+            ui.horizontal(|ui| {
+                ui.label(tr("leaderboard.player_name"));
+                ui.text_edit_singleline(&mut self.leaderboard.player_name);
+            });
+
+            let mut choices: Vec<Difficulty> = Difficulty::PRESETS.to_vec();
+            choices.extend(self.leaderboard.by_difficulty.keys().copied());
+            choices.push(self.current_difficulty);
+            choices.sort();
+            choices.dedup();
+            egui::ComboBox::new("difficulty", tr("leaderboard.difficulty"))
+                .selected_text(selected.label())
+                .show_ui(ui, |ui| {
+                    for difficulty in choices {
+                        ui.selectable_value(&mut selected, difficulty, difficulty.label());
+                    }
+                });
+
+            let entries = self.leaderboard.entries(selected);
+            if entries.is_empty() {
+                ui.label(tr("leaderboard.empty"));
+                return;
+            }
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                egui::Grid::new("leaderboard").striped(true).show(ui, |ui| {
+                    for header in [
+                        "leaderboard.rank",
+                        "leaderboard.player",
+                        "leaderboard.time",
+                        "leaderboard.bbbv_per_second",
+                        "leaderboard.date",
+                    ] {
+                        ui.strong(tr(header));
+                    }
+                    ui.label("");
+                    ui.end_row();
+
+                    for (rank, entry) in entries.iter().enumerate() {
+                        ui.label((rank + 1).to_string());
+                        ui.label(&entry.player_name);
+                        ui.label(tr_args(
+                            "units.seconds",
+                            &[(
+                                "seconds",
+                                &format!("{:.2}", f64::from(entry.time_ms) / 1000.0),
+                            )],
+                        ));
+                        ui.label(format!("{:.2}", entry.bbbv_per_second));
+                        ui.label(entry.date());
+                        if ui.button(tr("leaderboard.watch")).clicked() {
+                            *self.watch = Some(entry.replay.clone());
+                        }
+                        ui.end_row();
+                    }
+                });
+            });
+        });
+
+        ui.data_mut(|d| d.insert_temp(selected_id, selected));
+        response
+    }
+}
@@ -0,0 +1,172 @@
+use egui::Widget;
+
+use crate::{
+    i18n::{tr, tr_args},
+    replay::{CURSOR_TRAIL_MS, REPLAY_SPEEDS, ReplayPlayer},
+    theme::Theme,
+    widgets::{GameBoard, GameStatusBar},
+};
+
+/// Plays a recorded round back on the regular game board.
+pub struct ReplayScene<'a> {
+    rect: egui::Rect,
+    player: &'a mut ReplayPlayer,
+}
+impl<'a> ReplayScene<'a> {
+    pub fn new(rect: egui::Rect, player: &'a mut ReplayPlayer) -> Self {
+        Self { rect, player }
+    }
+}
+
+impl Widget for ReplayScene<'_> {
+    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+        self.player.advance(ui.input(|i| i.stable_dt));
+        if self.player.playing {
+            ui.ctx().request_repaint();
+        }
+
+        let header_height = self.rect.height() - self.rect.width();
+        let status_bar_rect = egui::Rect::from_min_size(
+            self.rect.min,
+            egui::vec2(self.rect.width(), header_height * 0.6),
+        );
+        let controls_rect = egui::Rect::from_min_size(
+            egui::pos2(self.rect.min.x, status_bar_rect.max.y),
+            egui::vec2(self.rect.width(), header_height - status_bar_rect.height()),
+        );
+        let game_board_rect = egui::Rect::from_min_size(
+            egui::pos2(self.rect.min.x, self.rect.min.y + header_height),
+            egui::vec2(self.rect.width(), self.rect.width()),
+        );
+
+        egui::Frame::NONE
+            .show(ui, |ui| {
+                ui.put(
+                    status_bar_rect,
+                    GameStatusBar::new(&self.player.round, status_bar_rect),
+                );
+                ui.put(controls_rect, ReplayControls::new(self.player));
+                let response = ui.put(
+                    game_board_rect,
+                    GameBoard::new(&mut self.player.round, game_board_rect).interactive(false),
+                );
+                paint_cursor_trail(ui, self.player, game_board_rect);
+                response
+            })
+            .response
+    }
+}
+
+fn paint_cursor_trail(ui: &egui::Ui, player: &ReplayPlayer, board_rect: egui::Rect) {
+    let now = player.position_ms();
+    let trail = player.replay.cursor_trail(now);
+    let cell_size = crate::utils::calculate_cell_size(
+        board_rect,
+        player.round.board_width,
+        player.round.board_height,
+    );
+    let to_screen = |column: f32, row: f32| board_rect.min + egui::vec2(column, row) * cell_size;
+    let painter = ui.painter_at(board_rect);
+    let theme = Theme::load(ui.ctx());
+
+    for pair in trail.windows(2) {
+        if let [from, to] = pair {
+            let age = now.saturating_sub(to.time_ms) as f32 / CURSOR_TRAIL_MS as f32;
+            let alpha = (1.0 - age).clamp(0.0, 1.0);
+            painter.line_segment(
+                [
+                    to_screen(from.column, from.row),
+                    to_screen(to.column, to.row),
+                ],
+                egui::Stroke::new(cell_size * 0.12, theme.replay_cursor.gamma_multiply(alpha)),
+            );
+        }
+    }
+    if let Some(last) = trail.last() {
+        painter.circle_filled(
+            to_screen(last.column, last.row),
+            cell_size * 0.15,
+            theme.replay_cursor,
+        );
+    }
+}
+
+/// Play/pause, frame stepping, speed and timeline controls for a [`ReplayPlayer`].
+struct ReplayControls<'a> {
+    player: &'a mut ReplayPlayer,
+}
+impl<'a> ReplayControls<'a> {
+    fn new(player: &'a mut ReplayPlayer) -> Self {
+        Self { player }
+    }
+}
+
+impl Widget for ReplayControls<'_> {
+    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+        ui.horizontal_centered(|ui| {
+            ui.spacing_mut().item_spacing.x = 4.0;
+            if ui
+                .button("⏮")
+                .on_hover_text(tr("replay.previous"))
+                .clicked()
+            {
+                self.player.step_backward();
+            }
+            let (play_label, play_hint) = if self.player.playing {
+                ("⏸", tr("replay.pause"))
+            } else {
+                ("⏵", tr("replay.play"))
+            };
+            if ui.button(play_label).on_hover_text(play_hint).clicked() {
+                self.player.toggle_playing();
+            }
+            if ui.button("⏭").on_hover_text(tr("replay.next")).clicked() {
+                self.player.step_forward();
+            }
+
+            egui::ComboBox::from_id_salt("ReplaySpeed")
+                .width(56.0)
+                .selected_text(format!("{}×", self.player.speed))
+                .show_ui(ui, |ui| {
+                    for speed in REPLAY_SPEEDS {
+                        ui.selectable_value(&mut self.player.speed, speed, format!("{speed}×"));
+                    }
+                });
+
+            let duration = self.player.duration_ms();
+            let time_label = tr_args(
+                "replay.time",
+                &[
+                    (
+                        "position",
+                        &format!("{:.1}", f64::from(self.player.position_ms()) / 1000.0),
+                    ),
+                    ("duration", &format!("{:.1}", f64::from(duration) / 1000.0)),
+                ],
+            );
+            ui.spacing_mut().slider_width =
+                (ui.available_width() - ui.spacing().item_spacing.x - 80.0).max(40.0);
+            let mut position = self.player.position_ms();
+            let timeline = ui.add(
+                egui::Slider::new(&mut position, 0..=duration)
+                    .show_value(false)
+                    .trailing_fill(true),
+            );
+            if timeline.changed() {
+                self.player.seek(position);
+            }
+            let metrics = &self.player.metrics;
+            ui.label(time_label).on_hover_text(tr_args(
+                "replay.metrics",
+                &[
+                    ("solved", &metrics.solved_bbbv),
+                    ("bbbv", &metrics.bbbv),
+                    ("speed", &format!("{:.2}", metrics.bbbv_per_second())),
+                    ("clicks", &metrics.clicks()),
+                    ("efficiency", &format!("{:.0}", metrics.efficiency())),
+                ],
+            ));
+        })
+        .response
+    }
+}
@@ -0,0 +1,156 @@
+use egui::Widget;
+
+use crate::export::ExportError;
+use crate::i18n::{tr, tr_args};
+use crate::save::slots::SaveSlots;
+use crate::widgets::MiniMap;
+
+/// What the player asked of the slots; the app carries it out with access to storage.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SlotRequest {
+    Save(String),
+    Load(String),
+    Delete(String),
+    /// Write the slot to a standalone file (native only).
+    Export(String),
+}
+
+/// Where the last exported slot was written, or why it was not, shown under that slot.
+#[derive(Clone, Debug)]
+pub struct SlotExport {
+    pub name: String,
+    pub result: Result<std::path::PathBuf, ExportError>,
+}
+
+impl SlotExport {
+    fn show(&self, ui: &mut egui::Ui) {
+        match &self.result {
+            Ok(path) => {
+                ui.label(tr_args("save_slots.exported", &[("path", &path.display())]));
+            }
+            Err(err) => {
+                ui.colored_label(
+                    ui.visuals().error_fg_color,
+                    tr_args("save_slots.export_failed", &[("error", err)]),
+                );
+            }
+        }
+    }
+}
+
+/// Lists the save slots with a thumbnail and progress, and saves the current round.
+pub struct SaveSlotsScene<'a> {
+    rect: egui::Rect,
+    slots: &'a SaveSlots,
+    request: &'a mut Option<SlotRequest>,
+    export: Option<&'a SlotExport>,
+}
+impl<'a> SaveSlotsScene<'a> {
+    pub fn new(
+        rect: egui::Rect,
+        slots: &'a SaveSlots,
+        request: &'a mut Option<SlotRequest>,
+        export: Option<&'a SlotExport>,
+    ) -> Self {
+        Self {
+            rect,
+            slots,
+            request,
+            export,
+        }
+    }
+}
+
+impl Widget for SaveSlotsScene<'_> {
+    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+        let name_id = egui::Id::new("SLOT_NAME");
+        let mut name: String = ui.data(|d| d.get_temp(name_id)).unwrap_or_default();
+
+        let response = super::page(ui, self.rect, tr("save_slots.heading"), |ui| {
+            ui.horizontal(|ui| {
+                ui.text_edit_singleline(&mut name);
+                let trimmed = name.trim();
+                let button = ui.add_enabled(
+                    !trimmed.is_empty(),
+                    egui::Button::new(tr("save_slots.save")),
+                );
+                let button = if self.slots.get(trimmed).is_some() {
+                    button.on_hover_text(tr("save_slots.save_hint"))
+                } else {
+                    button
+                };
+                if button.clicked() {
+                    *self.request = Some(SlotRequest::Save(trimmed.to_owned()));
+                }
+            });
+
+            if self.slots.slots().is_empty() {
+                ui.label(tr("save_slots.empty"));
+                return;
+            }
+            let thumbnail_size = (self.rect.width() * 0.15).max(32.0);
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for slot in self.slots.slots() {
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        if let Ok(saved) = &slot.round {
+                            ui.add(MiniMap::new(&saved.round.board_data, thumbnail_size));
+                        }
+                        ui.vertical(|ui| {
+                            ui.strong(&slot.name);
+                            ui.label(slot.date());
+                            match &slot.round {
+                                Ok(saved) => ui.label(tr_args(
+                                    "save_slots.summary",
+                                    &[
+                                        ("width", &saved.round.board_width),
+                                        ("height", &saved.round.board_height),
+                                        ("mines", &saved.round.total_mine),
+                                        (
+                                            "cleared",
+                                            &format!("{:.0}", saved.round.progress() * 100.0),
+                                        ),
+                                    ],
+                                )),
+                                Err(err) => {
+                                    ui.colored_label(ui.visuals().error_fg_color, err.to_string())
+                                }
+                            };
+                            ui.horizontal(|ui| {
+                                if ui
+                                    .add_enabled(
+                                        slot.round.is_ok(),
+                                        egui::Button::new(tr("save_slots.load")),
+                                    )
+                                    .clicked()
+                                {
+                                    *self.request = Some(SlotRequest::Load(slot.name.clone()));
+                                }
+                                if ui.button(tr("save_slots.delete")).clicked() {
+                                    *self.request = Some(SlotRequest::Delete(slot.name.clone()));
+                                }
+                                #[cfg(not(target_arch = "wasm32"))]
+                                if ui
+                                    .add_enabled(
+                                        slot.round.is_ok(),
+                                        egui::Button::new(tr("save_slots.export")),
+                                    )
+                                    .on_hover_text(tr("save_slots.export_hint"))
+                                    .clicked()
+                                {
+                                    *self.request = Some(SlotRequest::Export(slot.name.clone()));
+                                }
+                            });
+                            if let Some(export) = self.export.filter(|it| it.name == slot.name) {
+                                export.show(ui);
+                            }
+                        });
+                    });
+                }
+            });
+        });
+
+        ui.data_mut(|d| d.insert_temp(name_id, name));
+        response
+    }
+}
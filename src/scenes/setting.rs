@@ -0,0 +1,434 @@
+use egui::Widget;
+
+use crate::bindings::{Binding, BoundAction, InputBindings};
+use crate::config::{BoardConfig, ConfigError};
+use crate::data::GameSettings;
+use crate::i18n::{Locale, tr, tr_args};
+use crate::skin::SkinLibrary;
+use crate::statistics::Difficulty;
+use crate::theme::{AnimationSpeed, Appearance, NumberPalette, ThemePreference};
+
+/// The settings as typed, checked on every frame but only applied once valid.
+#[derive(Clone, Debug)]
+struct SettingsDraft {
+    width: usize,
+    height: usize,
+    mines: usize,
+    safe_first_click: bool,
+    single_click_chord: bool,
+}
+
+impl SettingsDraft {
+    fn new(settings: &GameSettings) -> Self {
+        let board = settings.board();
+        Self {
+            width: board.width(),
+            height: board.height(),
+            mines: board.mines(),
+            safe_first_click: settings.safe_first_click(),
+            single_click_chord: settings.single_click_chord(),
+        }
+    }
+    fn validate(&self) -> Result<GameSettings, ConfigError> {
+        GameSettings::new(
+            BoardConfig::new(self.width, self.height, self.mines)?,
+            self.safe_first_click,
+            self.single_click_chord,
+        )
+    }
+}
+
+/// Board size, mine count and gameplay options, kept in a draft until applied.
+///
+/// Applying fills `apply`. Control bindings, the skin, the appearance and the language
+/// change right away, as they need no new round.
+pub struct SettingScene<'a> {
+    rect: egui::Rect,
+    settings: &'a GameSettings,
+    bindings: &'a mut InputBindings,
+    skins: &'a mut SkinLibrary,
+    appearance: &'a mut Appearance,
+    /// `None` follows the system.
+    locale: &'a mut Option<Locale>,
+    apply: &'a mut Option<GameSettings>,
+}
+impl<'a> SettingScene<'a> {
+    fn draft_id() -> egui::Id {
+        egui::Id::new("SETTINGS_DRAFT")
+    }
+    /// Starts the draft over from `settings`, dropping what was typed the last time the
+    /// scene was left without applying it.
+    pub fn reset_draft(ctx: &egui::Context, settings: &GameSettings) {
+        ctx.data_mut(|d| d.insert_temp(Self::draft_id(), SettingsDraft::new(settings)));
+    }
+    pub fn new(
+        rect: egui::Rect,
+        settings: &'a GameSettings,
+        bindings: &'a mut InputBindings,
+        skins: &'a mut SkinLibrary,
+        appearance: &'a mut Appearance,
+        locale: &'a mut Option<Locale>,
+        apply: &'a mut Option<GameSettings>,
+    ) -> Self {
+        Self {
+            rect,
+            settings,
+            bindings,
+            skins,
+            appearance,
+            locale,
+            apply,
+        }
+    }
+}
+
+/// The language of the interface, by default the system's.
+fn language_ui(ui: &mut egui::Ui, locale: &mut Option<Locale>) {
+    ui.horizontal(|ui| {
+        ui.label(tr("settings.language"));
+        let system = tr_args(
+            "settings.system_language",
+            &[("language", &Locale::detect().native_name())],
+        );
+        egui::ComboBox::from_id_salt("language")
+            .selected_text(locale.map_or_else(|| system.clone(), |it| it.native_name().to_owned()))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(locale, None, system);
+                for it in Locale::ALL {
+                    ui.add_enabled_ui(it.has_font(), |ui| {
+                        ui.selectable_value(locale, Some(it), it.native_name())
+                            .on_disabled_hover_text(tr("settings.no_font"));
+                    });
+                }
+            });
+    });
+}
+
+/// The theme, and how the numbers are told apart.
+fn appearance_ui(ui: &mut egui::Ui, appearance: &mut Appearance) {
+    ui.horizontal(|ui| {
+        ui.label(tr("settings.theme"));
+        for theme in ThemePreference::ALL {
+            ui.selectable_value(&mut appearance.theme, theme, theme.label());
+        }
+    })
+    .response
+    .on_hover_text(tr("settings.theme_hint"));
+    ui.horizontal(|ui| {
+        ui.label(tr("settings.numbers"));
+        for palette in NumberPalette::ALL {
+            ui.selectable_value(&mut appearance.palette, palette, palette.label());
+        }
+    })
+    .response
+    .on_hover_text(tr("settings.numbers_hint"));
+    ui.checkbox(&mut appearance.shape_cues, tr("settings.shape_cues"))
+        .on_hover_text(tr("settings.shape_cues_hint"));
+    motion_ui(ui, appearance);
+}
+
+/// Sets the speed of each board animation, or turns them all off at once.
+fn motion_ui(ui: &mut egui::Ui, appearance: &mut Appearance) {
+    let motion = &mut appearance.motion;
+    let enabled = !motion.reduced_motion;
+    for (speed, label, hint) in [
+        (
+            &mut motion.cascade,
+            "settings.cascade",
+            "settings.cascade_hint",
+        ),
+        (
+            &mut motion.explosion,
+            "settings.explosion",
+            "settings.explosion_hint",
+        ),
+        (
+            &mut motion.win,
+            "settings.win_animation",
+            "settings.win_animation_hint",
+        ),
+    ] {
+        ui.add_enabled_ui(enabled, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(tr(label));
+                for it in AnimationSpeed::ALL {
+                    ui.selectable_value(speed, it, it.label());
+                }
+            })
+            .response
+            .on_hover_text(tr(hint));
+        });
+    }
+    ui.checkbox(&mut motion.reduced_motion, tr("settings.reduced_motion"))
+        .on_hover_text(tr("settings.reduced_motion_hint"));
+}
+
+/// Picks the skin among the installed packs, and reloads it after its files were edited.
+fn skin_ui(ui: &mut egui::Ui, skins: &mut SkinLibrary) {
+    ui.horizontal(|ui| {
+        ui.label(tr("settings.skin"));
+        let mut selected = skins.selected().map(str::to_owned);
+        let name = match (&skins.manifest().name, skins.selected()) {
+            (name, _) if !name.is_empty() => name.clone(),
+            (_, Some(id)) => id.to_owned(),
+            (_, None) => tr("settings.built_in_skin").to_owned(),
+        };
+        egui::ComboBox::from_id_salt("skin")
+            .selected_text(name)
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut selected, None, tr("settings.built_in_skin"));
+                for id in skins.installed() {
+                    ui.selectable_value(&mut selected, Some(id.clone()), id);
+                }
+            });
+        if selected.as_deref() != skins.selected() {
+            skins.select(ui.ctx(), selected);
+        }
+        if ui
+            .button(tr("settings.reload_skin"))
+            .on_hover_text(tr_args(
+                "settings.reload_skin_hint",
+                &[("folder", &crate::skin::SKINS_DIR)],
+            ))
+            .clicked()
+        {
+            skins.reload(ui.ctx());
+        }
+    });
+    if let Some(err) = skins.error() {
+        ui.colored_label(ui.visuals().error_fg_color, err.to_string());
+    }
+    ui.weak(tr("settings.skin_drop_hint"));
+}
+
+/// Lists the bindings of every action, each with a button to remove it, and captures the
+/// next key press or click as a new binding.
+fn controls_ui(ui: &mut egui::Ui, bindings: &mut InputBindings) {
+    let capture_id = egui::Id::new("REBINDING");
+    let mut capturing: Option<BoundAction> = ui.data(|d| d.get_temp(capture_id)).flatten();
+    if let Some(action) = capturing {
+        match ui.input(|i| i.events.iter().find_map(Binding::from_event)) {
+            Some(Binding::Key(shortcut)) if shortcut.logical_key == egui::Key::Escape => {
+                capturing = None;
+            }
+            Some(binding) => {
+                bindings.add(action, binding);
+                capturing = None;
+            }
+            None => {}
+        }
+    }
+
+    ui.horizontal(|ui| {
+        if ui
+            .button(tr("settings.swap_buttons"))
+            .on_hover_text(tr("settings.swap_buttons_hint"))
+            .clicked()
+        {
+            bindings.swap_mouse_buttons();
+        }
+        if ui.button(tr("settings.reset_controls")).clicked() {
+            *bindings = InputBindings::default();
+        }
+    });
+    egui::Grid::new("input_bindings").show(ui, |ui| {
+        for action in BoundAction::ALL {
+            ui.label(action.label());
+            ui.horizontal(|ui| {
+                ui.spacing_mut().item_spacing.x = 4.0;
+                for binding in bindings.bindings(action).to_vec() {
+                    if ui
+                        .button(format!("{} ×", binding.label(ui.ctx())))
+                        .on_hover_text(tr("settings.remove_binding"))
+                        .clicked()
+                    {
+                        bindings.remove(action, &binding);
+                    }
+                }
+                if capturing == Some(action) {
+                    ui.weak(tr("settings.capturing"));
+                } else if ui.button(tr("settings.add_binding")).clicked() {
+                    capturing = Some(action);
+                }
+            });
+            ui.end_row();
+        }
+    });
+    for conflict in bindings.conflicts() {
+        ui.colored_label(ui.visuals().error_fg_color, conflict.describe(ui.ctx()));
+    }
+
+    ui.data_mut(|d| d.insert_temp(capture_id, capturing));
+}
+
+impl Widget for SettingScene<'_> {
+    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+        let draft_id = Self::draft_id();
+        let mut draft: SettingsDraft = ui
+            .data(|d| d.get_temp(draft_id))
+            .unwrap_or_else(|| SettingsDraft::new(self.settings));
+
+        let response = super::page(ui, self.rect, tr("settings.heading"), |ui| {
+            // More rows than fit the smallest window, so Start is reached by scrolling.
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                language_ui(ui, self.locale);
+
+                ui.horizontal(|ui| {
+                    let current = Difficulty::classify(draft.width, draft.height, draft.mines);
+                    for preset in Difficulty::PRESETS {
+                        if ui
+                            .selectable_label(current == preset, preset.label())
+                            .clicked()
+                        {
+                            (draft.width, draft.height, draft.mines) = preset.dimensions();
+                        }
+                    }
+                    if matches!(current, Difficulty::Custom { .. }) {
+                        ui.strong(tr("settings.custom"));
+                    } else {
+                        ui.weak(tr("settings.custom"));
+                    }
+                });
+
+                egui::Grid::new("board_settings").show(ui, |ui| {
+                    ui.label(tr("settings.width"));
+                    ui.add(egui::DragValue::new(&mut draft.width));
+                    ui.end_row();
+                    ui.label(tr("settings.height"));
+                    ui.add(egui::DragValue::new(&mut draft.height));
+                    ui.end_row();
+                    ui.label(tr("settings.mines"));
+                    ui.add(egui::DragValue::new(&mut draft.mines));
+                    ui.end_row();
+                });
+
+                ui.separator();
+                ui.checkbox(&mut draft.safe_first_click, tr("settings.safe_first_click"))
+                    .on_hover_text(tr("settings.safe_first_click_hint"));
+                ui.checkbox(
+                    &mut draft.single_click_chord,
+                    tr("settings.single_click_chord"),
+                )
+                .on_hover_text(tr("settings.single_click_chord_hint"));
+                appearance_ui(ui, self.appearance);
+                skin_ui(ui, self.skins);
+                egui::CollapsingHeader::new(tr("settings.controls"))
+                    .id_salt("controls")
+                    .show(ui, |ui| {
+                        egui::ScrollArea::vertical()
+                            .max_height(self.rect.height() / 2.0)
+                            .show(ui, |ui| controls_ui(ui, self.bindings));
+                    });
+
+                ui.separator();
+                match draft.validate() {
+                    Ok(settings) => {
+                        if ui.button(tr("settings.start")).clicked() {
+                            *self.apply = Some(settings);
+                        }
+                    }
+                    Err(err) => {
+                        ui.colored_label(
+                            ui.visuals().error_fg_color,
+                            tr_args("settings.cannot_start", &[("error", &err)]),
+                        );
+                        ui.add_enabled(false, egui::Button::new(tr("settings.start")));
+                    }
+                }
+            });
+        });
+
+        ui.data_mut(|d| d.insert_temp(draft_id, draft));
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::accesskit::{self, Node, Role};
+
+    use super::SettingScene;
+    use crate::bindings::InputBindings;
+    use crate::data::GameSettings;
+    use crate::skin::SkinLibrary;
+    use crate::theme::Appearance;
+
+    /// Where Start is after scrolling the page as far as `scroll` points down, with the
+    /// scene in the space the smallest window leaves it.
+    fn start_button_after_scrolling(scroll: f32) -> accesskit::Rect {
+        let ctx = egui::Context::default();
+        ctx.enable_accesskit();
+        let rect = egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(400.0, 450.0));
+        let settings = GameSettings::default();
+        let mut bindings = InputBindings::default();
+        let mut skins = SkinLibrary::default();
+        let mut appearance = Appearance::default();
+        let mut locale = None;
+        let mut apply = None;
+        let mut start = None;
+        for frame in 0..120 {
+            let mut events = vec![egui::Event::PointerMoved(rect.center())];
+            if frame == 1 {
+                events.push(egui::Event::MouseWheel {
+                    unit: egui::MouseWheelUnit::Point,
+                    delta: egui::vec2(0.0, -scroll),
+                    modifiers: egui::Modifiers::NONE,
+                });
+            }
+            let input = egui::RawInput {
+                screen_rect: Some(rect),
+                time: Some(f64::from(frame) / 60.0),
+                events,
+                ..Default::default()
+            };
+            let output = ctx.run(input, |ctx| {
+                egui::CentralPanel::default()
+                    .frame(egui::Frame::NONE)
+                    .show(ctx, |ui| {
+                        ui.put(
+                            rect,
+                            SettingScene::new(
+                                rect,
+                                &settings,
+                                &mut bindings,
+                                &mut skins,
+                                &mut appearance,
+                                &mut locale,
+                                &mut apply,
+                            ),
+                        );
+                    });
+            });
+            let nodes = output
+                .platform_output
+                .accesskit_update
+                .expect("AccessKit is enabled")
+                .nodes;
+            start = nodes
+                .iter()
+                .map(|(_, node)| node)
+                .find(|it: &&Node| {
+                    it.role() == Role::Button && it.label() == Some("Start new round")
+                })
+                .and_then(|it| it.bounds())
+                .or(start);
+        }
+        start.expect("Start is on the page")
+    }
+
+    #[test]
+    fn start_can_be_scrolled_to_in_the_smallest_window() {
+        let before = start_button_after_scrolling(0.0);
+        assert!(before.y0 > 450.0, "page fits without scrolling: {before:?}");
+        let after = start_button_after_scrolling(2000.0);
+        assert!(
+            after.y0 < before.y0,
+            "page scrolls: {before:?} to {after:?}"
+        );
+        assert!(
+            after.y1 <= 450.0,
+            "Start ends at {} after scrolling",
+            after.y1
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use egui::Widget;
+
+use crate::i18n::{tr, tr_args};
+use crate::statistics::{Difficulty, DifficultyStatistics, PlayerStatistics};
+
+/// Lifetime results per difficulty, with buttons to reset them.
+pub struct StatisticsScene<'a> {
+    rect: egui::Rect,
+    statistics: &'a mut PlayerStatistics,
+}
+impl<'a> StatisticsScene<'a> {
+    pub fn new(rect: egui::Rect, statistics: &'a mut PlayerStatistics) -> Self {
+        Self { rect, statistics }
+    }
+}
+
+impl Widget for StatisticsScene<'_> {
+    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+        let mut reset = None;
+        let mut reset_all = false;
+
+        let response = super::page(ui, self.rect, tr("statistics.heading"), |ui| {
+            // Presets are always listed; custom boards only once played.
+            let mut rows: Vec<(Difficulty, DifficultyStatistics)> = Difficulty::PRESETS
+                .into_iter()
+                .filter(|it| !self.statistics.by_difficulty.contains_key(it))
+                .map(|it| (it, DifficultyStatistics::default()))
+                .collect();
+            rows.extend(
+                self.statistics
+                    .by_difficulty
+                    .iter()
+                    .map(|(difficulty, stats)| (*difficulty, stats.clone())),
+            );
+            rows.sort_by_key(|(difficulty, _)| *difficulty);
+
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                egui::Grid::new("statistics").striped(true).show(ui, |ui| {
+                    for header in [
+                        "statistics.difficulty",
+                        "statistics.played",
+                        "statistics.won",
+                        "statistics.win_rate",
+                        "statistics.streak",
+                        "statistics.best_streak",
+                        "statistics.best_time",
+                        "statistics.average_time",
+                        "statistics.average_bbbv_per_second",
+                    ] {
+                        ui.strong(tr(header));
+                    }
+                    ui.label("");
+                    ui.end_row();
+
+                    for (difficulty, stats) in &rows {
+                        ui.label(difficulty.label());
+                        ui.label(stats.games_played.to_string());
+                        ui.label(stats.wins.to_string());
+                        ui.label(format!("{:.0}%", stats.win_rate() * 100.0));
+                        ui.label(stats.current_streak.to_string());
+                        ui.label(stats.best_streak.to_string());
+                        ui.label(format_seconds(stats.best_time_ms.map(f64::from)));
+                        ui.label(format_seconds(stats.average_time_ms()));
+                        ui.label(
+                            stats
+                                .average_bbbv_per_second()
+                                .map_or_else(|| "–".to_owned(), |it| format!("{it:.2}")),
+                        );
+                        if ui
+                            .add_enabled(
+                                stats.games_played > 0,
+                                egui::Button::new(tr("statistics.reset")),
+                            )
+                            .clicked()
+                        {
+                            reset = Some(*difficulty);
+                        }
+                        ui.end_row();
+                    }
+                });
+            });
+
+            if ui
+                .add_enabled(
+                    !self.statistics.by_difficulty.is_empty(),
+                    egui::Button::new(tr("statistics.reset_all")),
+                )
+                .clicked()
+            {
+                reset_all = true;
+            }
+        });
+
+        if let Some(difficulty) = reset {
+            self.statistics.reset(difficulty);
+        }
+        if reset_all {
+            self.statistics.reset_all();
+        }
+        response
+    }
+}
+
+fn format_seconds(time_ms: Option<f64>) -> String {
+    time_ms.map_or_else(
+        || "–".to_owned(),
+        |it| {
+            tr_args(
+                "units.seconds",
+                &[("seconds", &format!("{:.2}", it / 1000.0))],
+            )
+        },
+    )
+}
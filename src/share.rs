@@ -0,0 +1,182 @@
+//! Board links: a mine layout packed into the page URL fragment.
+//!
+//! The fragment is `v1-<width>x<height>-<mines>`, where `<mines>` is the board's mine map,
+//! one bit per cell in row-major order (least significant bit first), encoded as
+//! unpadded URL-safe base64. It does not depend on the random generator, so a link keeps
+//! opening the same board across versions. Saves pack their boards the same way, with
+//! [`encode_cell_map`].
+
+use base64::Engine as _;
+
+use crate::config::{BoardConfig, ConfigError};
+use crate::data::{CellPos, RoundData};
+use crate::i18n::{tr, tr_args};
+use crate::replay::Replay;
+
+const VERSION: &str = "v1";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ShareLinkError {
+    UnknownVersion(String),
+    Malformed,
+    InvalidMines,
+    InvalidBoard(ConfigError),
+}
+
+impl std::fmt::Display for ShareLinkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self {
+            Self::UnknownVersion(version) => {
+                tr_args("share.unknown_version", &[("version", version)])
+            }
+            Self::Malformed => tr("share.malformed").to_owned(),
+            Self::InvalidMines => tr("share.invalid_mines").to_owned(),
+            Self::InvalidBoard(err) => tr_args("share.invalid_board", &[("error", err)]),
+        };
+        f.write_str(&message)
+    }
+}
+
+impl std::error::Error for ShareLinkError {}
+
+/// A board decoded from a link.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SharedBoard {
+    pub width: usize,
+    pub height: usize,
+    pub mines: Vec<CellPos>,
+}
+
+impl SharedBoard {
+    pub fn from_replay(replay: &Replay) -> Self {
+        Self {
+            width: replay.width,
+            height: replay.height,
+            mines: replay.mines.clone(),
+        }
+    }
+    /// A new round on this board, whose mines stay put even with a safe first click.
+    pub fn new_round(&self) -> RoundData {
+        let mut round = RoundData::from_board(
+            self.mines.len(),
+            crate::game_logic::board_from_mines(self.width, self.height, &self.mines),
+        );
+        round.fixed_layout = true;
+        round
+    }
+    /// Encodes the board as a URL fragment, without the leading `#`.
+    pub fn to_fragment(&self) -> String {
+        format!(
+            "{VERSION}-{}x{}-{}",
+            self.width,
+            self.height,
+            encode_cell_map(self.width, self.height, &self.mines)
+        )
+    }
+    /// Decodes a URL fragment, with or without the leading `#`.
+    ///
+    /// # Errors
+    ///
+    /// Fails on an unknown version, a malformed size, a mine map of the wrong length, or a
+    /// board that [`BoardConfig`] rejects.
+    pub fn from_fragment(fragment: &str) -> Result<Self, ShareLinkError> {
+        let fragment = fragment.trim_start_matches('#');
+        let mut parts = fragment.splitn(3, '-');
+        let (Some(version), Some(size), Some(mines)) = (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(ShareLinkError::Malformed);
+        };
+        if version != VERSION {
+            return Err(ShareLinkError::UnknownVersion(version.to_owned()));
+        }
+        let (width, height) = size
+            .split_once('x')
+            .and_then(|(width, height)| Some((width.parse().ok()?, height.parse().ok()?)))
+            .ok_or(ShareLinkError::Malformed)?;
+        let mines = decode_cell_map(width, height, mines).ok_or(ShareLinkError::InvalidMines)?;
+        BoardConfig::new(width, height, mines.len()).map_err(ShareLinkError::InvalidBoard)?;
+        Ok(Self {
+            width,
+            height,
+            mines,
+        })
+    }
+}
+
+/// Packs a set of cells of a `width` × `height` board into the mine map of a link.
+pub fn encode_cell_map(width: usize, height: usize, cells: &[CellPos]) -> String {
+    let mut bits = vec![0_u8; (width * height).div_ceil(8)];
+    for cell in cells {
+        let index = cell.x * width + cell.y;
+        if let Some(byte) = bits.get_mut(index / 8) {
+            *byte |= 1 << (index % 8);
+        }
+    }
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bits)
+}
+
+/// Unpacks a map written by [`encode_cell_map`], row by row. `None` when it is not valid
+/// base64 or its length does not match the board.
+pub fn decode_cell_map(width: usize, height: usize, map: &str) -> Option<Vec<CellPos>> {
+    let bits = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(map)
+        .ok()?;
+    let cell_count = usize::checked_mul(width, height)?;
+    if cell_count == 0 || bits.len() != cell_count.div_ceil(8) {
+        return None;
+    }
+    let cells = (0..cell_count)
+        .filter(|index| {
+            bits.get(index / 8)
+                .is_some_and(|byte| byte & (1 << (index % 8)) != 0)
+        })
+        .map(|index| CellPos::new(index / width, index % width))
+        .collect();
+    Some(cells)
+}
+
+/// The page URL without its fragment.
+#[cfg(target_arch = "wasm32")]
+pub fn page_url() -> Option<String> {
+    let href = web_sys::window()?.location().href().ok()?;
+    Some(href.split('#').next().unwrap_or_default().to_owned())
+}
+
+/// Puts the board into the page URL and returns the full link.
+#[cfg(target_arch = "wasm32")]
+pub fn publish_board(board: &SharedBoard) -> Option<String> {
+    let fragment = board.to_fragment();
+    web_sys::window()?.location().set_hash(&fragment).ok()?;
+    Some(format!("{}#{fragment}", page_url()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SharedBoard;
+    use crate::action::GameAction;
+    use crate::data::CellPos;
+
+    #[test]
+    fn shared_board_keeps_its_mines_after_a_safe_first_click() {
+        let board = crate::board_format::read("*.....\n......\n...*..\n.....*\n").expect("valid");
+        let shared = SharedBoard {
+            width: board.width(),
+            height: board.height(),
+            mines: board.get_mine_positions(),
+        };
+        let decoded = SharedBoard::from_fragment(&shared.to_fragment()).expect("valid link");
+        assert_eq!(decoded, shared, "fragment round trips");
+
+        // Next to the top-left mine, where a safe opening would have to move it.
+        let first = CellPos::new(1, 1);
+        let mut round = decoded.new_round();
+        round.make_safe_opening(&first);
+        round.perform(GameAction::Reveal(first));
+        assert_eq!(round.replay.mines, shared.mines, "replay keeps the mines");
+        assert_eq!(
+            round.board_data.get_mine_positions(),
+            shared.mines,
+            "board keeps the mines"
+        );
+    }
+}
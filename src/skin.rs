@@ -0,0 +1,366 @@
+//! Skin packs: the sprites of the cells and the face, read from a directory or a zip
+//! holding a [`MANIFEST_FILE`] and one SVG or PNG file per sprite in [`SPRITES`].
+
+mod zip;
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::app::GameImageSource;
+use crate::i18n::tr_args;
+
+/// Storage key of the name of the pack in use, `None` for the built-in skin.
+pub const SKIN_KEY: &str = "skin";
+/// Where skin packs are installed, in the working directory like the saved rounds.
+pub const SKINS_DIR: &str = "skins";
+pub const MANIFEST_FILE: &str = "skin.ron";
+
+/// Every sprite a skin provides. Unless the manifest points it at another file, a sprite
+/// is read from the file named after it with an `.svg` or `.png` extension.
+pub const SPRITES: [&str; 27] = [
+    "type0",
+    "type1",
+    "type2",
+    "type3",
+    "type4",
+    "type5",
+    "type6",
+    "type7",
+    "type8",
+    "type1_yellow",
+    "type2_yellow",
+    "type3_yellow",
+    "type4_yellow",
+    "type5_yellow",
+    "type6_yellow",
+    "type7_yellow",
+    "type8_yellow",
+    "closed",
+    "flag",
+    "mine",
+    "mine_red",
+    "mine_wrong",
+    "face_unpressed",
+    "face_pressed",
+    "face_active",
+    "face_win",
+    "face_lose",
+];
+
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct SkinManifest {
+    pub name: String,
+    pub author: String,
+    /// Files of the sprites not named after them, by sprite.
+    pub files: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SkinError {
+    Io(String),
+    Archive(String),
+    MissingManifest,
+    Manifest(String),
+    /// The manifest names a file for a sprite that is not in [`SPRITES`].
+    UnknownSprite(String),
+    MissingSprite(String),
+    MissingFile {
+        sprite: String,
+        file: String,
+    },
+    UnsupportedFormat(String),
+}
+
+impl std::fmt::Display for SkinError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self {
+            Self::Io(message) => tr_args("skin.io", &[("message", message)]),
+            Self::Archive(message) => tr_args("skin.archive", &[("message", message)]),
+            Self::MissingManifest => {
+                tr_args("skin.missing_manifest", &[("manifest", &MANIFEST_FILE)])
+            }
+            Self::Manifest(message) => tr_args(
+                "skin.manifest",
+                &[("manifest", &MANIFEST_FILE), ("message", message)],
+            ),
+            Self::UnknownSprite(sprite) => tr_args(
+                "skin.unknown_sprite",
+                &[("manifest", &MANIFEST_FILE), ("sprite", sprite)],
+            ),
+            Self::MissingSprite(sprite) => tr_args("skin.missing_sprite", &[("sprite", sprite)]),
+            Self::MissingFile { sprite, file } => {
+                tr_args("skin.missing_file", &[("sprite", sprite), ("file", file)])
+            }
+            Self::UnsupportedFormat(file) => tr_args("skin.unsupported_format", &[("file", file)]),
+        };
+        f.write_str(&message)
+    }
+}
+
+impl std::error::Error for SkinError {}
+
+pub struct Skin {
+    pub manifest: SkinManifest,
+    pub images: GameImageSource,
+}
+
+impl Default for Skin {
+    /// The built-in skin, compiled into the game.
+    fn default() -> Self {
+        Self {
+            manifest: ron::from_str(include_str!("../assets/game/skin.ron")).unwrap_or_default(),
+            images: GameImageSource::default(),
+        }
+    }
+}
+
+impl Skin {
+    /// Reads a skin from its files, keyed by their path next to the manifest. `id` keeps
+    /// the images of different packs apart in egui's cache.
+    ///
+    /// # Errors
+    ///
+    /// Fails when the manifest is missing or invalid, or a sprite has no image.
+    pub fn from_files(id: &str, files: &BTreeMap<String, Vec<u8>>) -> Result<Self, SkinError> {
+        let manifest = files.get(MANIFEST_FILE).ok_or(SkinError::MissingManifest)?;
+        let manifest: SkinManifest = ron::from_str(&String::from_utf8_lossy(manifest))
+            .map_err(|err| SkinError::Manifest(err.to_string()))?;
+        if let Some(sprite) = manifest
+            .files
+            .keys()
+            .find(|it| !SPRITES.contains(&it.as_str()))
+        {
+            return Err(SkinError::UnknownSprite(sprite.clone()));
+        }
+
+        let load = |sprite: &str| -> Result<egui::ImageSource<'static>, SkinError> {
+            let file = match manifest.files.get(sprite) {
+                Some(file) => file.clone(),
+                None => ["svg", "png"]
+                    .into_iter()
+                    .map(|extension| format!("{sprite}.{extension}"))
+                    .find(|it| files.contains_key(it))
+                    .ok_or_else(|| SkinError::MissingSprite(sprite.to_owned()))?,
+            };
+            let extension = file.rsplit_once('.').map(|it| it.1.to_ascii_lowercase());
+            if !matches!(extension.as_deref(), Some("svg" | "png")) {
+                return Err(SkinError::UnsupportedFormat(file));
+            }
+            let bytes: Arc<[u8]> = files
+                .get(&file)
+                .ok_or_else(|| SkinError::MissingFile {
+                    sprite: sprite.to_owned(),
+                    file: file.clone(),
+                })?
+                .as_slice()
+                .into();
+            Ok(egui::ImageSource::Bytes {
+                uri: format!("bytes://skin/{id}/{file}").into(),
+                bytes: egui::load::Bytes::Shared(bytes),
+            })
+        };
+        let numbers = |suffix: &str| {
+            (0..=8)
+                .map(|number| match number {
+                    // A revealed empty cell looks the same whether chordable or not.
+                    0 => load("type0"),
+                    _ => load(&format!("type{number}{suffix}")),
+                })
+                .collect::<Result<Vec<_>, _>>()
+        };
+        let images = GameImageSource {
+            cell_num: numbers("")?,
+            cell_num_yellow: numbers("_yellow")?,
+            cell_closed: load("closed")?,
+            cell_flag: load("flag")?,
+            cell_mine: load("mine")?,
+            cell_mine_red: load("mine_red")?,
+            cell_mine_wrong: load("mine_wrong")?,
+            face_unpressed: load("face_unpressed")?,
+            face_pressed: load("face_pressed")?,
+            face_active: load("face_active")?,
+            face_win: load("face_win")?,
+            face_lose: load("face_lose")?,
+        };
+        Ok(Self { manifest, images })
+    }
+
+    /// Reads a zipped skin, whose files may sit in a folder inside the archive.
+    ///
+    /// # Errors
+    ///
+    /// Fails when `data` is not a zip archive, or holds no valid skin.
+    pub fn from_zip(id: &str, data: &[u8]) -> Result<Self, SkinError> {
+        let files = zip::read(data)?;
+        let root = files
+            .keys()
+            .filter_map(|it| it.strip_suffix(MANIFEST_FILE))
+            .filter(|it| it.is_empty() || it.ends_with('/'))
+            .min_by_key(|it| it.len())
+            .ok_or(SkinError::MissingManifest)?
+            .to_owned();
+        let files = files
+            .into_iter()
+            .filter_map(|(name, data)| Some((name.strip_prefix(&root)?.to_owned(), data)))
+            .collect();
+        Self::from_files(id, &files)
+    }
+
+    /// Reads the pack installed under [`SKINS_DIR`] as `id`, a directory or a `.zip`.
+    ///
+    /// # Errors
+    ///
+    /// Fails when the pack cannot be read, or holds no valid skin.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load(id: &str) -> Result<Self, SkinError> {
+        let path = std::path::Path::new(SKINS_DIR).join(id);
+        let io = |err: std::io::Error| SkinError::Io(format!("{}: {err}", path.display()));
+        if !path.is_dir() {
+            return Self::from_zip(id, &std::fs::read(&path).map_err(io)?);
+        }
+        let mut files = BTreeMap::new();
+        for entry in std::fs::read_dir(&path).map_err(io)? {
+            let entry = entry.map_err(io)?;
+            if entry.file_type().map_err(io)?.is_file() {
+                files.insert(
+                    entry.file_name().to_string_lossy().into_owned(),
+                    std::fs::read(entry.path()).map_err(io)?,
+                );
+            }
+        }
+        Self::from_files(id, &files)
+    }
+}
+
+/// Whether a file is named like a zipped skin pack.
+pub fn is_zip(name: &str) -> bool {
+    name.to_ascii_lowercase().ends_with(".zip")
+}
+
+/// Names of the packs under [`SKINS_DIR`], directories and `.zip` files alike.
+#[cfg(not(target_arch = "wasm32"))]
+fn installed_on_disk() -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(SKINS_DIR) else {
+        return vec![];
+    };
+    entries
+        .filter_map(Result::ok)
+        .filter(|it| it.path().is_dir() || is_zip(&it.file_name().to_string_lossy()))
+        .map(|it| it.file_name().to_string_lossy().into_owned())
+        .collect()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn installed_on_disk() -> Vec<String> {
+    vec![]
+}
+
+/// The skin in use and the packs it can be switched to at any time.
+#[derive(Default)]
+pub struct SkinLibrary {
+    /// The pack in use by its name under [`SKINS_DIR`], or `None` for the built-in skin.
+    selected: Option<String>,
+    installed: Vec<String>,
+    /// Zipped packs dropped onto the window, which the web build has nowhere else to keep.
+    dropped: BTreeMap<String, Vec<u8>>,
+    skin: Skin,
+    /// Why the last pack failed to load, while the skin before it stays in use.
+    error: Option<SkinError>,
+}
+
+impl SkinLibrary {
+    /// Finds the installed packs and switches to `selected`, keeping the built-in skin if
+    /// it cannot be loaded.
+    pub fn new(selected: Option<String>) -> Self {
+        let mut library = Self::default();
+        library.rescan();
+        library.load(selected);
+        library
+    }
+    pub fn images(&self) -> &GameImageSource {
+        &self.skin.images
+    }
+    pub fn manifest(&self) -> &SkinManifest {
+        &self.skin.manifest
+    }
+    pub fn selected(&self) -> Option<&str> {
+        self.selected.as_deref()
+    }
+    pub fn installed(&self) -> &[String] {
+        &self.installed
+    }
+    pub fn error(&self) -> Option<&SkinError> {
+        self.error.as_ref()
+    }
+
+    fn read(&self, id: &str) -> Result<Skin, SkinError> {
+        if let Some(data) = self.dropped.get(id) {
+            return Skin::from_zip(id, data);
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            Skin::load(id)
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            Err(SkinError::Io(format!("{id} is not installed")))
+        }
+    }
+    fn load(&mut self, selected: Option<String>) {
+        let skin = match &selected {
+            Some(id) => self.read(id),
+            None => Ok(Skin::default()),
+        };
+        match skin {
+            Ok(skin) => {
+                self.skin = skin;
+                self.selected = selected;
+                self.error = None;
+            }
+            Err(err) => {
+                log::warn!("Failed to load skin: {err}");
+                self.error = Some(err);
+            }
+        }
+    }
+    /// Switches to another pack, or to the built-in skin with `None`, right away.
+    pub fn select(&mut self, ctx: &egui::Context, selected: Option<String>) {
+        self.load(selected);
+        // Edited packs keep their image URIs, so nothing cached may outlive a switch.
+        ctx.forget_all_images();
+    }
+    /// Looks for packs again and reloads the one in use, picking up edits to its files.
+    pub fn reload(&mut self, ctx: &egui::Context) {
+        self.rescan();
+        self.select(ctx, self.selected.clone());
+    }
+    fn rescan(&mut self) {
+        let mut installed = installed_on_disk();
+        for id in self.dropped.keys() {
+            if !installed.contains(id) {
+                installed.push(id.clone());
+            }
+        }
+        installed.sort();
+        self.installed = installed;
+    }
+    /// Installs a zipped pack dropped onto the window and switches to it. The native build
+    /// also copies it into [`SKINS_DIR`] for later sessions.
+    pub fn install(&mut self, ctx: &egui::Context, name: &str, data: Vec<u8>) {
+        if let Err(err) = Skin::from_zip(name, &data) {
+            log::warn!("Failed to install skin {name}: {err}");
+            self.error = Some(err);
+            return;
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Err(err) = std::fs::create_dir_all(SKINS_DIR)
+            .and_then(|()| std::fs::write(std::path::Path::new(SKINS_DIR).join(name), &data))
+        {
+            log::warn!("Failed to copy skin {name} into {SKINS_DIR}: {err}");
+        }
+        self.dropped.insert(name.to_owned(), data);
+        self.selected = Some(name.to_owned());
+        self.reload(ctx);
+    }
+}
@@ -0,0 +1,125 @@
+//! Just enough of the zip format to unpack a skin pack.
+//!
+//! The archive is read from its central directory, found through the end-of-directory
+//! record at the end of the file. Multi-byte integers are little-endian:
+//!
+//! | record               | fields used                                                   |
+//! |----------------------|---------------------------------------------------------------|
+//! | end of directory     | entry count at 10, directory offset at 16                     |
+//! | directory entry      | method at 10, sizes at 20 and 24, name, local offset at 42    |
+//! | local header         | name and extra lengths at 26 and 28, then the data            |
+//!
+//! Only stored and deflated entries are supported, which is what every common zip tool
+//! writes. Zip64 archives and encryption are not.
+
+use std::collections::BTreeMap;
+
+use crate::skin::SkinError;
+
+const END_OF_DIRECTORY: u32 = 0x0605_4b50;
+const DIRECTORY_ENTRY: u32 = 0x0201_4b50;
+const LOCAL_HEADER: u32 = 0x0403_4b50;
+const END_OF_DIRECTORY_SIZE: usize = 22;
+const METHOD_STORED: u16 = 0;
+const METHOD_DEFLATED: u16 = 8;
+
+fn corrupt(message: &str) -> SkinError {
+    SkinError::Archive(message.to_owned())
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn at(data: &'a [u8], offset: usize) -> Self {
+        Self { data, offset }
+    }
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], SkinError> {
+        let bytes = self
+            .data
+            .get(self.offset..self.offset + len)
+            .ok_or_else(|| corrupt("unexpected end of archive"))?;
+        self.offset += len;
+        Ok(bytes)
+    }
+    fn skip(&mut self, len: usize) -> Result<(), SkinError> {
+        self.bytes(len).map(|_| ())
+    }
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], SkinError> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.bytes(N)?);
+        Ok(array)
+    }
+    fn u16(&mut self) -> Result<u16, SkinError> {
+        Ok(u16::from_le_bytes(self.array()?))
+    }
+    fn u32(&mut self) -> Result<u32, SkinError> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+    fn usize(&mut self) -> Result<usize, SkinError> {
+        Ok(self.u32()? as usize)
+    }
+}
+
+/// Unpacks every file in `data`, keyed by its path inside the archive.
+///
+/// # Errors
+///
+/// Fails when `data` is not a zip archive, or holds an entry that cannot be unpacked.
+pub fn read(data: &[u8]) -> Result<BTreeMap<String, Vec<u8>>, SkinError> {
+    // The end-of-directory record is followed by a comment of up to 64 KiB.
+    let end = (0..=data.len().saturating_sub(END_OF_DIRECTORY_SIZE))
+        .rev()
+        .take(END_OF_DIRECTORY_SIZE + usize::from(u16::MAX))
+        .find(|it| Reader::at(data, *it).u32().ok() == Some(END_OF_DIRECTORY))
+        .ok_or_else(|| corrupt("not a zip archive"))?;
+    let mut record = Reader::at(data, end + 10);
+    let count = record.u16()?;
+    record.skip(4)?;
+    let mut entry = Reader::at(data, record.usize()?);
+
+    let mut files = BTreeMap::new();
+    for _ in 0..count {
+        if entry.u32()? != DIRECTORY_ENTRY {
+            return Err(corrupt("broken central directory"));
+        }
+        entry.skip(6)?;
+        let method = entry.u16()?;
+        entry.skip(8)?;
+        let compressed_size = entry.usize()?;
+        let size = entry.usize()?;
+        let name_len = usize::from(entry.u16()?);
+        let extra_len = usize::from(entry.u16()?);
+        let comment_len = usize::from(entry.u16()?);
+        entry.skip(8)?;
+        let local_offset = entry.usize()?;
+        let name = String::from_utf8_lossy(entry.bytes(name_len)?).into_owned();
+        entry.skip(extra_len + comment_len)?;
+        if name.ends_with('/') {
+            continue;
+        }
+
+        let mut local = Reader::at(data, local_offset);
+        if local.u32()? != LOCAL_HEADER {
+            return Err(corrupt("broken local header"));
+        }
+        local.skip(22)?;
+        let skip = usize::from(local.u16()?) + usize::from(local.u16()?);
+        local.skip(skip)?;
+        let compressed = local.bytes(compressed_size)?;
+        let content = match method {
+            METHOD_STORED => compressed.to_vec(),
+            METHOD_DEFLATED => miniz_oxide::inflate::decompress_to_vec_with_limit(compressed, size)
+                .map_err(|err| SkinError::Archive(format!("{name}: {err}")))?,
+            other => {
+                return Err(SkinError::Archive(format!(
+                    "{name}: compression method {other} is not supported"
+                )));
+            }
+        };
+        files.insert(name, content);
+    }
+    Ok(files)
+}
@@ -0,0 +1,211 @@
+//! Lifetime results per difficulty: rounds played and won, streaks, and the best and
+//! average times.
+
+use std::collections::BTreeMap;
+
+use crate::i18n::{tr, tr_args};
+use crate::metrics::RoundMetrics;
+
+/// Storage key of the [`PlayerStatistics`].
+pub const STATISTICS_KEY: &str = "statistics";
+
+/// A board configuration that statistics and records are grouped by.
+#[derive(
+    serde::Deserialize, serde::Serialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord,
+)]
+pub enum Difficulty {
+    Beginner,
+    Intermediate,
+    Expert,
+    Custom {
+        width: usize,
+        height: usize,
+        mines: usize,
+    },
+}
+
+impl Difficulty {
+    pub const PRESETS: [Self; 3] = [Self::Beginner, Self::Intermediate, Self::Expert];
+
+    pub fn classify(width: usize, height: usize, mines: usize) -> Self {
+        Self::PRESETS
+            .into_iter()
+            .find(|it| it.dimensions() == (width, height, mines))
+            .unwrap_or(Self::Custom {
+                width,
+                height,
+                mines,
+            })
+    }
+    /// Width, height and mine count.
+    pub fn dimensions(self) -> (usize, usize, usize) {
+        match self {
+            Self::Beginner => (9, 9, 10),
+            Self::Intermediate => (16, 16, 40),
+            Self::Expert => (30, 16, 99),
+            Self::Custom {
+                width,
+                height,
+                mines,
+            } => (width, height, mines),
+        }
+    }
+    pub fn label(self) -> String {
+        match self {
+            Self::Beginner => tr("difficulty.beginner").to_owned(),
+            Self::Intermediate => tr("difficulty.intermediate").to_owned(),
+            Self::Expert => tr("difficulty.expert").to_owned(),
+            Self::Custom {
+                width,
+                height,
+                mines,
+            } => tr_args(
+                "difficulty.custom",
+                &[("width", &width), ("height", &height), ("mines", &mines)],
+            ),
+        }
+    }
+}
+
+/// Lifetime results on one [`Difficulty`].
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct DifficultyStatistics {
+    pub games_played: u32,
+    pub wins: u32,
+    pub current_streak: u32,
+    pub best_streak: u32,
+    pub best_time_ms: Option<u32>,
+    /// Sum over won games, for the averages.
+    pub total_win_time_ms: u64,
+    pub total_bbbv_per_second: f64,
+}
+
+impl DifficultyStatistics {
+    pub fn record(&mut self, metrics: &RoundMetrics) {
+        self.games_played += 1;
+        if !metrics.won {
+            self.current_streak = 0;
+            return;
+        }
+        self.wins += 1;
+        self.current_streak += 1;
+        self.best_streak = self.best_streak.max(self.current_streak);
+        self.best_time_ms = Some(
+            self.best_time_ms
+                .map_or(metrics.time_ms, |it| it.min(metrics.time_ms)),
+        );
+        self.total_win_time_ms += u64::from(metrics.time_ms);
+        self.total_bbbv_per_second += f64::from(metrics.bbbv_per_second());
+    }
+    /// Share of games won, from 0 to 1.
+    pub fn win_rate(&self) -> f64 {
+        if self.games_played == 0 {
+            return 0.0;
+        }
+        f64::from(self.wins) / f64::from(self.games_played)
+    }
+    pub fn average_time_ms(&self) -> Option<f64> {
+        (self.wins > 0).then(|| self.total_win_time_ms as f64 / f64::from(self.wins))
+    }
+    pub fn average_bbbv_per_second(&self) -> Option<f64> {
+        (self.wins > 0).then(|| self.total_bbbv_per_second / f64::from(self.wins))
+    }
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct PlayerStatistics {
+    pub by_difficulty: BTreeMap<Difficulty, DifficultyStatistics>,
+}
+
+impl PlayerStatistics {
+    pub fn record(&mut self, difficulty: Difficulty, metrics: &RoundMetrics) {
+        self.by_difficulty
+            .entry(difficulty)
+            .or_default()
+            .record(metrics);
+    }
+    pub fn reset(&mut self, difficulty: Difficulty) {
+        self.by_difficulty.remove(&difficulty);
+    }
+    pub fn reset_all(&mut self) {
+        self.by_difficulty.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Difficulty, DifficultyStatistics};
+    use crate::metrics::RoundMetrics;
+
+    fn round(won: bool, time_ms: u32, solved_bbbv: usize) -> RoundMetrics {
+        RoundMetrics {
+            bbbv: solved_bbbv,
+            solved_bbbv,
+            time_ms,
+            won,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn a_loss_ends_the_streak_but_keeps_the_best() {
+        let mut stats = DifficultyStatistics::default();
+        for won in [true, true, true, false, true] {
+            stats.record(&round(won, 10_000, 10));
+        }
+        assert_eq!(stats.current_streak, 1, "one win since the loss");
+        assert_eq!(stats.best_streak, 3, "the three wins before it");
+        stats.record(&round(false, 10_000, 10));
+        assert_eq!(stats.current_streak, 0, "reset by the loss");
+        assert_eq!(stats.best_streak, 3, "unchanged by the loss");
+    }
+
+    #[test]
+    fn averages_count_only_won_rounds() {
+        let mut stats = DifficultyStatistics::default();
+        assert_eq!(stats.win_rate(), 0.0, "no rounds played");
+        assert_eq!(stats.average_time_ms(), None, "no rounds won");
+        assert_eq!(stats.average_bbbv_per_second(), None, "no rounds won");
+
+        stats.record(&round(true, 10_000, 20));
+        stats.record(&round(false, 1_000, 1));
+        stats.record(&round(true, 20_000, 20));
+        stats.record(&round(false, 1_000, 1));
+        assert_eq!(stats.win_rate(), 0.5, "two of four");
+        assert_eq!(stats.average_time_ms(), Some(15_000.0), "10s and 20s");
+        assert_eq!(stats.average_bbbv_per_second(), Some(1.5), "2 and 1 3BV/s");
+        assert_eq!(stats.best_time_ms, Some(10_000), "faster win");
+    }
+
+    #[test]
+    fn classifies_the_presets_and_everything_else_as_custom() {
+        assert_eq!(
+            Difficulty::classify(9, 9, 10),
+            Difficulty::Beginner,
+            "9×9×10"
+        );
+        assert_eq!(
+            Difficulty::classify(16, 16, 40),
+            Difficulty::Intermediate,
+            "16×16×40"
+        );
+        assert_eq!(
+            Difficulty::classify(30, 16, 99),
+            Difficulty::Expert,
+            "30×16×99"
+        );
+        for (width, height, mines) in [(9, 9, 11), (16, 30, 99), (16, 16, 10)] {
+            assert_eq!(
+                Difficulty::classify(width, height, mines),
+                Difficulty::Custom {
+                    width,
+                    height,
+                    mines
+                },
+                "{width}×{height}×{mines}"
+            );
+        }
+    }
+}
@@ -0,0 +1,416 @@
+//! Colours of the window, the panels, the counters and the cell sprites, and how the
+//! board moves.
+
+use crate::i18n::tr;
+
+/// Storage key of the [`Appearance`].
+pub const APPEARANCE_KEY: &str = "appearance";
+
+/// The look picked in the settings.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct Appearance {
+    pub theme: ThemePreference,
+    pub palette: NumberPalette,
+    /// A faint shape behind each number, so they can be told apart without colour.
+    pub shape_cues: bool,
+    pub motion: Motion,
+}
+
+impl Appearance {
+    /// The theme to use while the system is in `system` mode, light when unknown.
+    pub fn resolve(self, system: Option<egui::Theme>) -> Theme {
+        let number_colors = match self.palette.colors() {
+            None if self.shape_cues => Some(CLASSIC_NUMBERS),
+            colors => colors,
+        };
+        Theme {
+            number_colors,
+            shape_cues: self.shape_cues,
+            ..self.theme.resolve(system)
+        }
+    }
+}
+
+/// How fast an animation plays, if at all.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AnimationSpeed {
+    Off,
+    Slow,
+    #[default]
+    Normal,
+    Fast,
+}
+
+impl AnimationSpeed {
+    pub const ALL: [Self; 4] = [Self::Off, Self::Slow, Self::Normal, Self::Fast];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Off => tr("animation.off"),
+            Self::Slow => tr("animation.slow"),
+            Self::Normal => tr("animation.normal"),
+            Self::Fast => tr("animation.fast"),
+        }
+    }
+    /// How many times faster than normal it plays, or `None` when off.
+    fn factor(self) -> Option<f32> {
+        match self {
+            Self::Off => None,
+            Self::Slow => Some(0.5),
+            Self::Normal => Some(1.0),
+            Self::Fast => Some(2.0),
+        }
+    }
+}
+
+/// The board's animations picked in the settings. They only change what is painted, so
+/// the board takes input while they play.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct Motion {
+    /// Cells opened by one reveal appear in a wave from the revealed cell.
+    pub cascade: AnimationSpeed,
+    /// The mine that went off flashes, then the other mines show one after another.
+    pub explosion: AnimationSpeed,
+    /// A wave of light sweeps across the board after a win.
+    pub win: AnimationSpeed,
+    /// Turns every animation off, whatever its speed.
+    pub reduced_motion: bool,
+}
+
+impl Motion {
+    fn id() -> egui::Id {
+        egui::Id::new("MOTION")
+    }
+    /// The motion of this frame, as set by the app.
+    pub fn load(ctx: &egui::Context) -> Self {
+        ctx.data(|d| d.get_temp(Self::id())).unwrap_or_default()
+    }
+    pub fn store(self, ctx: &egui::Context) {
+        ctx.data_mut(|d| d.insert_temp(Self::id(), self));
+    }
+    fn factor(self, speed: AnimationSpeed) -> Option<f32> {
+        speed.factor().filter(|_| !self.reduced_motion)
+    }
+    /// How many times faster than normal the reveal wave plays, or `None` when off.
+    pub fn cascade_factor(self) -> Option<f32> {
+        self.factor(self.cascade)
+    }
+    pub fn explosion_factor(self) -> Option<f32> {
+        self.factor(self.explosion)
+    }
+    pub fn win_factor(self) -> Option<f32> {
+        self.factor(self.win)
+    }
+}
+
+/// Colours of the numbers in the classic sprites, from 1 to 8.
+const CLASSIC_NUMBERS: [egui::Color32; 8] = [
+    egui::Color32::from_rgb(0, 0, 247),
+    egui::Color32::from_rgb(0, 119, 0),
+    egui::Color32::from_rgb(236, 0, 0),
+    egui::Color32::from_rgb(0, 0, 128),
+    egui::Color32::from_rgb(128, 0, 0),
+    egui::Color32::from_rgb(0, 128, 128),
+    egui::Color32::BLACK,
+    egui::Color32::from_rgb(112, 112, 112),
+];
+
+/// Colours that tell the numbers apart for a kind of colour blindness. Neighbouring
+/// numbers differ in lightness as well as hue, which every kind still sees.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NumberPalette {
+    /// The skin's own number sprites.
+    #[default]
+    Classic,
+    /// Weak green perception, the most common kind.
+    Deuteranopia,
+    /// Weak red perception, where reds look dark.
+    Protanopia,
+    /// Weak blue perception, where blue and green run together.
+    Tritanopia,
+}
+
+impl NumberPalette {
+    pub const ALL: [Self; 4] = [
+        Self::Classic,
+        Self::Deuteranopia,
+        Self::Protanopia,
+        Self::Tritanopia,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Classic => tr("palette.classic"),
+            Self::Deuteranopia => tr("palette.deuteranopia"),
+            Self::Protanopia => tr("palette.protanopia"),
+            Self::Tritanopia => tr("palette.tritanopia"),
+        }
+    }
+    /// Colours of the numbers from 1 to 8, or `None` to keep the skin's sprites.
+    pub fn colors(self) -> Option<[egui::Color32; 8]> {
+        use egui::Color32;
+        match self {
+            Self::Classic => None,
+            Self::Deuteranopia => Some([
+                Color32::from_rgb(0, 114, 178),
+                Color32::from_rgb(230, 120, 0),
+                Color32::from_rgb(170, 51, 119),
+                Color32::from_rgb(0, 50, 110),
+                Color32::from_rgb(136, 102, 0),
+                Color32::from_rgb(40, 170, 230),
+                Color32::BLACK,
+                Color32::from_rgb(112, 112, 112),
+            ]),
+            Self::Protanopia => Some([
+                Color32::from_rgb(0, 114, 178),
+                Color32::from_rgb(214, 160, 0),
+                Color32::from_rgb(86, 180, 233),
+                Color32::from_rgb(30, 30, 120),
+                Color32::from_rgb(120, 90, 0),
+                Color32::from_rgb(0, 150, 115),
+                Color32::BLACK,
+                Color32::from_rgb(112, 112, 112),
+            ]),
+            Self::Tritanopia => Some([
+                Color32::from_rgb(220, 30, 100),
+                Color32::from_rgb(0, 130, 120),
+                Color32::from_rgb(200, 0, 0),
+                Color32::from_rgb(80, 20, 140),
+                Color32::from_rgb(140, 100, 80),
+                Color32::from_rgb(0, 172, 193),
+                Color32::BLACK,
+                Color32::from_rgb(112, 112, 112),
+            ]),
+        }
+    }
+}
+
+/// The theme picked in the settings.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ThemePreference {
+    /// Light or dark along with the system's dark-mode setting, on native and web alike.
+    #[default]
+    System,
+    Light,
+    Dark,
+    HighContrast,
+}
+
+impl ThemePreference {
+    pub const ALL: [Self; 4] = [Self::System, Self::Light, Self::Dark, Self::HighContrast];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::System => tr("theme.system"),
+            Self::Light => tr("theme.light"),
+            Self::Dark => tr("theme.dark"),
+            Self::HighContrast => tr("theme.high_contrast"),
+        }
+    }
+    fn resolve(self, system: Option<egui::Theme>) -> Theme {
+        match self {
+            Self::System if system == Some(egui::Theme::Dark) => Theme::DARK,
+            Self::System | Self::Light => Theme::LIGHT,
+            Self::Dark => Theme::DARK,
+            Self::HighContrast => Theme::HIGH_CONTRAST,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Theme {
+    /// Whether egui's own widgets are drawn light on dark.
+    pub dark: bool,
+    /// Thick outlines and plain black and white around the board.
+    pub high_contrast: bool,
+    /// Behind the status bar and the board.
+    pub background: egui::Color32,
+    /// Behind the buttons at the bottom.
+    pub panel: egui::Color32,
+    pub counter: egui::Color32,
+    pub counter_lit: egui::Color32,
+    pub counter_unlit: egui::Color32,
+    /// Multiplied into every cell sprite, white to keep them as drawn.
+    pub cell_tint: egui::Color32,
+    /// The keyboard cursor and the minimap's view outline.
+    pub highlight: egui::Color32,
+    pub error: egui::Color32,
+    /// Colours of the numbers 1 to 8 drawn over revealed cells, or `None` to keep the
+    /// skin's number sprites.
+    pub number_colors: Option<[egui::Color32; 8]>,
+    pub shape_cues: bool,
+    /// Covers the board while the round is paused, under the word "Paused".
+    pub paused: egui::Color32,
+    pub paused_text: egui::Color32,
+    /// The ring that fills up while a touch is held long enough to flag.
+    pub long_press: egui::Color32,
+    /// The flash over the mine that went off, and its edge.
+    pub explosion: egui::Color32,
+    pub explosion_edge: egui::Color32,
+    /// The light of the win sweep, and the text of the best-time banner.
+    pub celebration: egui::Color32,
+    /// Behind the best-time banner.
+    pub banner: egui::Color32,
+    /// The pointer and its trail in the replay viewer.
+    pub replay_cursor: egui::Color32,
+    pub mini_map: MiniMapColors,
+}
+
+/// Colours of the minimap, one per kind of cell.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MiniMapColors {
+    /// Between the cells.
+    pub background: egui::Color32,
+    pub covered: egui::Color32,
+    pub flagged: egui::Color32,
+    pub revealed: egui::Color32,
+    pub mine: egui::Color32,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::LIGHT
+    }
+}
+
+impl Theme {
+    pub const LIGHT: Self = Self {
+        dark: false,
+        high_contrast: false,
+        background: egui::Color32::from_rgb(198, 198, 198),
+        panel: egui::Color32::BLACK,
+        counter: egui::Color32::BLACK,
+        counter_lit: egui::Color32::from_rgb(255, 0, 0),
+        counter_unlit: egui::Color32::from_rgb(64, 0, 0),
+        cell_tint: egui::Color32::WHITE,
+        highlight: egui::Color32::from_rgb(0, 0, 255),
+        error: egui::Color32::from_rgb(180, 30, 30),
+        number_colors: None,
+        shape_cues: false,
+        paused: egui::Color32::from_rgb(128, 128, 128),
+        paused_text: egui::Color32::WHITE,
+        long_press: egui::Color32::from_rgb(200, 0, 0),
+        explosion: egui::Color32::from_rgb(255, 200, 60),
+        explosion_edge: egui::Color32::from_rgb(230, 60, 20),
+        celebration: egui::Color32::GOLD,
+        banner: egui::Color32::from_black_alpha(200),
+        replay_cursor: egui::Color32::from_rgb(220, 40, 40),
+        mini_map: MiniMapColors {
+            background: egui::Color32::from_rgb(128, 128, 128),
+            covered: egui::Color32::from_rgb(160, 160, 160),
+            flagged: egui::Color32::from_rgb(200, 120, 40),
+            revealed: egui::Color32::from_rgb(230, 230, 230),
+            mine: egui::Color32::from_rgb(220, 40, 40),
+        },
+    };
+    pub const DARK: Self = Self {
+        dark: true,
+        high_contrast: false,
+        background: egui::Color32::from_rgb(40, 42, 46),
+        panel: egui::Color32::from_rgb(20, 21, 24),
+        counter: egui::Color32::from_rgb(12, 12, 12),
+        counter_lit: egui::Color32::from_rgb(230, 60, 50),
+        counter_unlit: egui::Color32::from_rgb(50, 12, 10),
+        cell_tint: egui::Color32::from_rgb(170, 172, 178),
+        highlight: egui::Color32::from_rgb(90, 160, 255),
+        error: egui::Color32::from_rgb(255, 110, 100),
+        number_colors: None,
+        shape_cues: false,
+        paused: egui::Color32::from_rgb(58, 60, 66),
+        paused_text: egui::Color32::from_rgb(220, 222, 228),
+        long_press: egui::Color32::from_rgb(230, 60, 50),
+        explosion: egui::Color32::from_rgb(230, 180, 60),
+        explosion_edge: egui::Color32::from_rgb(210, 60, 30),
+        celebration: egui::Color32::from_rgb(230, 195, 60),
+        banner: egui::Color32::from_black_alpha(220),
+        replay_cursor: egui::Color32::from_rgb(255, 100, 90),
+        mini_map: MiniMapColors {
+            background: egui::Color32::from_rgb(20, 21, 24),
+            covered: egui::Color32::from_rgb(90, 92, 98),
+            flagged: egui::Color32::from_rgb(200, 120, 40),
+            revealed: egui::Color32::from_rgb(160, 162, 168),
+            mine: egui::Color32::from_rgb(230, 60, 50),
+        },
+    };
+    pub const HIGH_CONTRAST: Self = Self {
+        dark: true,
+        high_contrast: true,
+        background: egui::Color32::BLACK,
+        panel: egui::Color32::BLACK,
+        counter: egui::Color32::BLACK,
+        counter_lit: egui::Color32::from_rgb(255, 255, 0),
+        counter_unlit: egui::Color32::from_rgb(40, 40, 0),
+        cell_tint: egui::Color32::WHITE,
+        highlight: egui::Color32::from_rgb(255, 255, 0),
+        error: egui::Color32::from_rgb(255, 80, 80),
+        number_colors: None,
+        shape_cues: false,
+        paused: egui::Color32::BLACK,
+        paused_text: egui::Color32::WHITE,
+        long_press: egui::Color32::from_rgb(255, 255, 0),
+        explosion: egui::Color32::from_rgb(255, 255, 0),
+        explosion_edge: egui::Color32::from_rgb(255, 80, 80),
+        celebration: egui::Color32::from_rgb(255, 255, 0),
+        banner: egui::Color32::BLACK,
+        replay_cursor: egui::Color32::from_rgb(0, 255, 255),
+        mini_map: MiniMapColors {
+            background: egui::Color32::BLACK,
+            covered: egui::Color32::from_rgb(110, 110, 110),
+            flagged: egui::Color32::from_rgb(255, 160, 0),
+            revealed: egui::Color32::WHITE,
+            mine: egui::Color32::from_rgb(255, 60, 60),
+        },
+    };
+
+    fn id() -> egui::Id {
+        egui::Id::new("THEME")
+    }
+    /// The theme of this frame, as set by the app.
+    pub fn load(ctx: &egui::Context) -> Self {
+        ctx.data(|d| d.get_temp(Self::id())).unwrap_or_default()
+    }
+    pub fn store(self, ctx: &egui::Context) {
+        ctx.data_mut(|d| d.insert_temp(Self::id(), self));
+    }
+
+    /// Width of the keyboard cursor and other outlines drawn over the board.
+    pub fn outline_width(&self) -> f32 {
+        if self.high_contrast { 4.0 } else { 2.0 }
+    }
+    /// The look of egui's own widgets, such as the buttons and the settings.
+    pub fn visuals(&self) -> egui::Visuals {
+        let mut visuals = if self.dark {
+            egui::Visuals::dark()
+        } else {
+            egui::Visuals::light()
+        };
+        visuals.window_stroke = egui::Stroke::NONE;
+        visuals.error_fg_color = self.error;
+        if self.high_contrast {
+            visuals.override_text_color = Some(egui::Color32::WHITE);
+            visuals.panel_fill = egui::Color32::BLACK;
+            visuals.window_fill = egui::Color32::BLACK;
+            visuals.extreme_bg_color = egui::Color32::BLACK;
+            visuals.selection.bg_fill = self.highlight;
+            visuals.selection.stroke = egui::Stroke::new(2.0, egui::Color32::BLACK);
+            let outline = egui::Stroke::new(2.0, egui::Color32::WHITE);
+            for widget in [
+                &mut visuals.widgets.noninteractive,
+                &mut visuals.widgets.inactive,
+                &mut visuals.widgets.hovered,
+                &mut visuals.widgets.active,
+                &mut visuals.widgets.open,
+            ] {
+                widget.bg_fill = egui::Color32::BLACK;
+                widget.weak_bg_fill = egui::Color32::BLACK;
+                widget.bg_stroke = outline;
+                widget.fg_stroke = outline;
+            }
+            visuals.widgets.hovered.bg_stroke = egui::Stroke::new(3.0, self.highlight);
+            visuals.widgets.active.bg_stroke = egui::Stroke::new(3.0, self.highlight);
+        }
+        visuals
+    }
+}
@@ -4,3 +4,8 @@ pub fn calculate_content_display_size(window_size: egui::Vec2) -> (f32, f32) {
         window_size.y,
     )
 }
+
+/// Side length of a square cell so that a `width` × `height` board fits inside `board_rect`.
+pub fn calculate_cell_size(board_rect: egui::Rect, width: usize, height: usize) -> f32 {
+    (board_rect.width() / width.max(1) as f32).min(board_rect.height() / height.max(1) as f32)
+}
@@ -1,8 +1,14 @@
-
+mod board_animation;
+mod board_view;
 mod function_panel;
 mod game_board;
 mod game_status_bar;
+mod mini_map;
+mod seven_segment;
 
+pub use crate::widgets::board_view::BoardView;
 pub use crate::widgets::function_panel::FunctionPanel;
 pub use crate::widgets::game_board::GameBoard;
 pub use crate::widgets::game_status_bar::GameStatusBar;
+pub use crate::widgets::mini_map::MiniMap;
+pub use crate::widgets::seven_segment::SevenSegmentDisplay;
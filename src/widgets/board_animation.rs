@@ -0,0 +1,293 @@
+//! Animations played over the board after an action: a wave of cells opening from a large
+//! reveal, the explosion on a loss with the other mines showing one after another, and a
+//! sweep of light after a win.
+//!
+//! The round itself changes at once. An animation only holds back how some cells are
+//! painted, timed by egui's clock, so the board keeps taking input while it plays.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::action::GameAction;
+use crate::data::{CellPos, RoundData, RoundEndingType, RoundState};
+use crate::theme::{Motion, Theme};
+
+/// Reveals opening fewer cells than this show at once.
+const MIN_CASCADE_CELLS: usize = 8;
+/// Seconds between one ring of the reveal wave and the next, at normal speed.
+const CASCADE_STEP: f32 = 0.025;
+/// The reveal wave never takes longer than this, however large the opening.
+const CASCADE_MAX: f32 = 0.8;
+/// Seconds a cell takes to grow to full size once it shows.
+const POP: f32 = 0.12;
+/// Seconds the flash over the mine that went off lasts.
+const FLASH: f32 = 0.4;
+/// Seconds between one ring of mines showing and the next after a loss.
+const MINE_STEP: f32 = 0.05;
+const MINES_MAX: f32 = 1.5;
+/// Seconds between one diagonal of the win sweep and the next.
+const WIN_STEP: f32 = 0.03;
+const WIN_MAX: f32 = 1.0;
+/// Seconds a cell glows as the win sweep passes over it.
+const WIN_GLOW: f32 = 0.35;
+
+/// Where the round was before the input of a frame, taken only on frames that bring input
+/// which could act on it. The cells an action opens are kept by the round itself, in
+/// [`RoundData::last_revealed`], so nothing of the board is copied here.
+pub struct BeforeAction {
+    events: usize,
+}
+
+impl BeforeAction {
+    pub fn take(round: &RoundData) -> Self {
+        Self {
+            events: round.replay.events.len(),
+        }
+    }
+    /// Actions in the replay before the input.
+    pub fn events(&self) -> usize {
+        self.events
+    }
+}
+
+/// How a cell is painted at a point of an animation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CellPhase {
+    /// Still as it was before the action.
+    Before,
+    /// Growing into its new look, from 0 to 1.
+    Showing(f32),
+    Settled,
+}
+
+/// The sweep of light after a win, over one diagonal of cells after another.
+#[derive(Clone, Copy, Debug)]
+struct WinSweep {
+    /// Seconds after the action when it reaches the top-left cell.
+    at: f32,
+    step: f32,
+    glow: f32,
+}
+
+/// The animation under way, kept across frames in the egui memory.
+#[derive(Clone, Debug)]
+pub struct BoardAnimation {
+    /// egui time of the action.
+    start: f64,
+    /// Seconds after the action when each cell held back shows its new look, by row and
+    /// column.
+    delays: Arc<BTreeMap<(usize, usize), f32>>,
+    pop: f32,
+    /// The mine that went off, and how long it flashes.
+    explosion: Option<(CellPos, f32)>,
+    win: Option<WinSweep>,
+    /// Seconds after the action when everything has settled.
+    end: f32,
+}
+
+/// Rings from `origin` out to `pos`, counting diagonal neighbours as one step.
+fn rings(origin: &CellPos, pos: (usize, usize)) -> usize {
+    origin.x.abs_diff(pos.0).max(origin.y.abs_diff(pos.1))
+}
+
+/// Seconds per ring for `count` rings at `step` apart, shortened to fit within `max`.
+fn step_within(step: f32, max: f32, count: usize, factor: f32) -> f32 {
+    (step / factor).min(max / factor / count.max(1) as f32)
+}
+
+impl BoardAnimation {
+    fn id() -> egui::Id {
+        egui::Id::new("BOARD_ANIMATION")
+    }
+    /// The animation still playing, if any.
+    pub fn load(ctx: &egui::Context) -> Option<Self> {
+        let now = ctx.input(|i| i.time);
+        ctx.data(|d| d.get_temp::<Option<Self>>(Self::id()))
+            .flatten()
+            .filter(|it| !it.is_done(now))
+    }
+    pub fn store(ctx: &egui::Context, animation: Option<Self>) {
+        ctx.data_mut(|d| d.insert_temp(Self::id(), animation));
+    }
+
+    /// The animation set off by the reveal or chord that changed the round since `before`,
+    /// or `None` when nothing calls for one.
+    pub fn after(
+        before: &BeforeAction,
+        round: &RoundData,
+        motion: Motion,
+        now: f64,
+    ) -> Option<Self> {
+        let events = &round.replay.events;
+        if events.len() <= before.events {
+            return None;
+        }
+        let Some(GameAction::Reveal(origin) | GameAction::Chord(origin)) =
+            events.last().map(|it| &it.action)
+        else {
+            return None;
+        };
+        let mut animation = Self {
+            start: now,
+            delays: Arc::default(),
+            pop: POP,
+            explosion: None,
+            win: None,
+            end: 0.0,
+        };
+        let mut delays = BTreeMap::new();
+        let changed: Vec<(usize, usize)> =
+            round.last_revealed.iter().map(|it| (it.x, it.y)).collect();
+        if let Some(factor) = motion
+            .cascade_factor()
+            .filter(|_| changed.len() >= MIN_CASCADE_CELLS)
+        {
+            let count = changed.iter().map(|it| rings(origin, *it)).max();
+            let step = step_within(CASCADE_STEP, CASCADE_MAX, count.unwrap_or_default(), factor);
+            animation.pop = POP / factor;
+            for pos in changed {
+                delays.insert(pos, rings(origin, pos) as f32 * step);
+            }
+        }
+        let settled = delays.values().fold(0.0_f32, |it, delay| it.max(*delay));
+
+        match &round.round_state_type {
+            RoundState::Ended(RoundEndingType::ClickedMine(exploded)) => {
+                if let Some(factor) = motion.explosion_factor() {
+                    animation.hold_mines(&mut delays, round, exploded, factor);
+                }
+            }
+            RoundState::Ended(RoundEndingType::Victory) => {
+                if let Some(factor) = motion.win_factor() {
+                    animation.sweep(&mut delays, round, settled, factor);
+                }
+            }
+            RoundState::NotStarted | RoundState::Playing => {}
+        }
+        if delays.is_empty() && animation.explosion.is_none() && animation.win.is_none() {
+            return None;
+        }
+        let last = delays.values().fold(settled, |it, delay| it.max(*delay));
+        animation.end = animation.end.max(last + animation.pop);
+        animation.delays = Arc::new(delays);
+        Some(animation)
+    }
+    /// Flashes the mine that went off, then shows the other mines and the wrong flags ring
+    /// by ring around it.
+    fn hold_mines(
+        &mut self,
+        delays: &mut BTreeMap<(usize, usize), f32>,
+        round: &RoundData,
+        exploded: &CellPos,
+        factor: f32,
+    ) {
+        let flash = FLASH / factor;
+        let cells: Vec<(usize, usize)> = round
+            .board_data
+            .cells
+            .iter()
+            .flatten()
+            .filter(|it| it.is_mine != it.is_flagged && it.position != *exploded)
+            .map(|it| (it.position.x, it.position.y))
+            .collect();
+        let count = cells.iter().map(|it| rings(exploded, *it)).max();
+        let step = step_within(MINE_STEP, MINES_MAX, count.unwrap_or_default(), factor);
+        for pos in cells {
+            delays.insert(pos, flash + rings(exploded, pos) as f32 * step);
+        }
+        self.explosion = Some((exploded.clone(), flash));
+        self.end = self.end.max(flash);
+    }
+    /// Sweeps light over the board once the last cells have opened, turning the mines
+    /// over as it passes them.
+    fn sweep(
+        &mut self,
+        delays: &mut BTreeMap<(usize, usize), f32>,
+        round: &RoundData,
+        at: f32,
+        factor: f32,
+    ) {
+        let diagonals = (round.board_width + round.board_height).saturating_sub(1);
+        let sweep = WinSweep {
+            at,
+            step: step_within(WIN_STEP, WIN_MAX, diagonals, factor),
+            glow: WIN_GLOW / factor,
+        };
+        for cell in round.board_data.cells.iter().flatten() {
+            if cell.is_mine {
+                let (row, column) = (cell.position.x, cell.position.y);
+                delays.insert((row, column), sweep.reaches(row, column));
+            }
+        }
+        self.end = self.end.max(sweep.reaches(diagonals, 0) + sweep.glow);
+        self.win = Some(sweep);
+    }
+
+    fn elapsed(&self, now: f64) -> f32 {
+        (now - self.start) as f32
+    }
+    fn is_done(&self, now: f64) -> bool {
+        self.elapsed(now) >= self.end
+    }
+    pub fn phase(&self, row: usize, column: usize, now: f64) -> CellPhase {
+        let Some(delay) = self.delays.get(&(row, column)) else {
+            return CellPhase::Settled;
+        };
+        match (self.elapsed(now) - delay) / self.pop {
+            progress if progress < 0.0 => CellPhase::Before,
+            progress if progress < 1.0 => CellPhase::Showing(progress),
+            _ => CellPhase::Settled,
+        }
+    }
+
+    /// The mine that went off, under its flash.
+    pub fn exploded(&self) -> Option<&CellPos> {
+        self.explosion.as_ref().map(|(pos, _)| pos)
+    }
+    /// Paints the flash over `rect`, the cell of the mine that went off.
+    pub fn paint_explosion(
+        &self,
+        painter: &egui::Painter,
+        rect: egui::Rect,
+        theme: &Theme,
+        now: f64,
+    ) {
+        let Some((_, flash)) = &self.explosion else {
+            return;
+        };
+        let progress = self.elapsed(now) / flash;
+        if !(0.0..1.0).contains(&progress) {
+            return;
+        }
+        let fade = 1.0 - progress;
+        let radius = rect.width() * (0.5 + 2.5 * progress);
+        painter.circle(
+            rect.center(),
+            radius,
+            theme.explosion.gamma_multiply(0.6 * fade),
+            egui::Stroke::new(
+                rect.width() * 0.3 * fade,
+                theme.explosion_edge.gamma_multiply(0.85 * fade),
+            ),
+        );
+    }
+    /// How much the win sweep lights up the cell, from 0 to 1.
+    pub fn glow(&self, row: usize, column: usize, now: f64) -> f32 {
+        let Some(sweep) = &self.win else {
+            return 0.0;
+        };
+        let progress = (self.elapsed(now) - sweep.reaches(row, column)) / sweep.glow;
+        if (0.0..1.0).contains(&progress) {
+            (progress * std::f32::consts::PI).sin()
+        } else {
+            0.0
+        }
+    }
+}
+
+impl WinSweep {
+    /// Seconds after the action when the sweep lights up the cell.
+    fn reaches(&self, row: usize, column: usize) -> f32 {
+        self.at + (row + column) as f32 * self.step
+    }
+}
@@ -0,0 +1,258 @@
+use crate::data::CellPos;
+
+/// Cells are never drawn larger than this when zooming in.
+const MAX_CELL_SIZE: f32 = 64.0;
+/// Cells are never drawn smaller than this when zooming out, so boards too large to fit
+/// open zoomed in with only a few thousand cells to paint.
+const MIN_CELL_SIZE: f32 = 8.0;
+
+/// Zoom and pan of the board, kept across frames in the egui memory. A zoom of 1 fits the
+/// whole board in its rect.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BoardView {
+    zoom: f32,
+    /// The furthest out this board can be zoomed, above 1 when it is too large to fit.
+    min_zoom: f32,
+    /// From the board rect's corner to the top-left cell's corner.
+    offset: egui::Vec2,
+}
+
+impl Default for BoardView {
+    fn default() -> Self {
+        Self {
+            zoom: 1.0,
+            min_zoom: 1.0,
+            offset: egui::Vec2::ZERO,
+        }
+    }
+}
+
+impl BoardView {
+    fn id() -> egui::Id {
+        egui::Id::new("BOARD_VIEW")
+    }
+    pub fn load(ctx: &egui::Context) -> Self {
+        ctx.data(|d| d.get_temp(Self::id())).unwrap_or_default()
+    }
+    pub fn store(self, ctx: &egui::Context) {
+        ctx.data_mut(|d| d.insert_temp(Self::id(), self));
+    }
+    /// Zooms back out until the whole board fits, or as far as it goes.
+    pub fn fit(ctx: &egui::Context) {
+        let view = Self::load(ctx);
+        Self {
+            zoom: view.min_zoom,
+            ..view
+        }
+        .store(ctx);
+    }
+    pub fn is_zoomed(&self) -> bool {
+        self.zoom > self.min_zoom
+    }
+    /// Whether part of the board is out of view.
+    pub fn is_cropped(&self) -> bool {
+        self.zoom > 1.0
+    }
+
+    /// Zooms by `factor` around `center`, which stays over the same spot of the board.
+    pub fn zoom_at(&mut self, layout: &BoardLayout, center: egui::Pos2, factor: f32) {
+        let (min_zoom, max_zoom) = layout.zoom_range();
+        let zoom = (self.zoom * factor).clamp(min_zoom, max_zoom);
+        let factor = zoom / self.zoom;
+        let anchor = center - layout.rect.min;
+        self.offset = anchor - (anchor - self.offset) * factor;
+        self.zoom = zoom;
+    }
+    pub fn pan(&mut self, delta: egui::Vec2) {
+        self.offset += delta;
+    }
+    /// Pans so the spot `column`, `row` cells from the top-left corner sits in the middle.
+    pub fn center_on(&mut self, layout: &BoardLayout, column: f32, row: f32) {
+        self.offset = layout.rect.size() / 2.0 - egui::vec2(column, row) * layout.cell_size;
+    }
+    /// Pans the least needed to bring a cell fully into view.
+    pub fn scroll_to(&mut self, layout: &BoardLayout, pos: &CellPos) {
+        let cell = layout.cell_rect(pos.x, pos.y);
+        let shift = |low: f32, high: f32, min: f32, max: f32| {
+            if low < min {
+                min - low
+            } else if high > max {
+                max - high
+            } else {
+                0.0
+            }
+        };
+        self.offset += egui::vec2(
+            shift(cell.min.x, cell.max.x, layout.rect.min.x, layout.rect.max.x),
+            shift(cell.min.y, cell.max.y, layout.rect.min.y, layout.rect.max.y),
+        );
+    }
+    /// Keeps the zoom in range, and the board covering the rect on every axis where it is
+    /// larger than the rect.
+    pub fn clamp(&mut self, layout: &BoardLayout) {
+        let (min_zoom, max_zoom) = layout.zoom_range();
+        let zoom = self.zoom.clamp(min_zoom, max_zoom);
+        let factor = zoom / self.zoom;
+        self.zoom = zoom;
+        self.min_zoom = min_zoom;
+        self.offset *= factor;
+        let content = layout.content_size() * factor;
+        let limit = |offset: f32, content: f32, viewport: f32| {
+            if content <= viewport {
+                0.0
+            } else {
+                offset.clamp(viewport - content, 0.0)
+            }
+        };
+        self.offset = egui::vec2(
+            limit(self.offset.x, content.x, layout.rect.width()),
+            limit(self.offset.y, content.y, layout.rect.height()),
+        );
+    }
+
+    pub fn layout(&self, rect: egui::Rect, width: usize, height: usize) -> BoardLayout {
+        let fit_cell_size = crate::utils::calculate_cell_size(rect, width, height);
+        BoardLayout {
+            rect,
+            origin: rect.min + self.offset,
+            cell_size: egui::Vec2::splat(fit_cell_size * self.zoom),
+            fit_cell_size,
+            width,
+            height,
+        }
+    }
+}
+
+/// Where each cell of the board lands on screen under a [`BoardView`].
+#[derive(Clone, Copy, Debug)]
+pub struct BoardLayout {
+    /// The part of the screen the board is shown in.
+    pub rect: egui::Rect,
+    /// Top-left corner of the top-left cell, outside `rect` when panned.
+    pub origin: egui::Pos2,
+    pub cell_size: egui::Vec2,
+    fit_cell_size: f32,
+    width: usize,
+    height: usize,
+}
+
+impl BoardLayout {
+    fn zoom_range(&self) -> (f32, f32) {
+        let min_zoom = (MIN_CELL_SIZE / self.fit_cell_size).max(1.0);
+        (min_zoom, (MAX_CELL_SIZE / self.fit_cell_size).max(min_zoom))
+    }
+    pub fn content_size(&self) -> egui::Vec2 {
+        egui::vec2(self.width as f32, self.height as f32) * self.cell_size
+    }
+    pub fn cell_rect(&self, row: usize, column: usize) -> egui::Rect {
+        egui::Rect::from_min_size(
+            self.origin + egui::vec2(column as f32, row as f32) * self.cell_size,
+            self.cell_size,
+        )
+    }
+    /// Position of `pointer` in cells from the board's top-left corner.
+    pub fn position_in_cells(&self, pointer: egui::Pos2) -> egui::Vec2 {
+        (pointer - self.origin) / self.cell_size
+    }
+    /// The cell shown under `pointer`, if any.
+    pub fn cell_at(&self, pointer: egui::Pos2) -> Option<CellPos> {
+        if !self.rect.contains(pointer) {
+            return None;
+        }
+        let cells = self.position_in_cells(pointer);
+        let (row, column) = (cells.y.floor(), cells.x.floor());
+        if row < 0.0 || column < 0.0 {
+            return None;
+        }
+        let pos = CellPos::new(row as usize, column as usize);
+        (pos.x < self.height && pos.y < self.width).then_some(pos)
+    }
+    /// The part of the board in view, in cells.
+    pub fn visible_cells(&self) -> egui::Rect {
+        egui::Rect::from_min_max(
+            self.position_in_cells(self.rect.min).to_pos2(),
+            self.position_in_cells(self.rect.max).to_pos2(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BoardView;
+    use crate::data::CellPos;
+
+    /// A 320×320 rect, so a 10×8 board fits with 32 point cells and zooms in to 64.
+    const RECT: egui::Rect = egui::Rect {
+        min: egui::pos2(100.0, 50.0),
+        max: egui::pos2(420.0, 370.0),
+    };
+
+    /// A point 1 point inside the cell `column` across and `row` down from `RECT`'s corner,
+    /// at `cell_size`.
+    fn inside(column: f32, row: f32, cell_size: f32) -> egui::Pos2 {
+        RECT.min + egui::vec2(column, row) * cell_size + egui::Vec2::splat(1.0)
+    }
+
+    #[test]
+    fn finds_cells_at_minimum_zoom() {
+        let layout = BoardView::default().layout(RECT, 10, 8);
+        assert_eq!(layout.cell_size, egui::Vec2::splat(32.0), "fits the width");
+        assert_eq!(
+            layout.cell_at(inside(0.0, 0.0, 32.0)),
+            Some(CellPos::new(0, 0)),
+            "top-left"
+        );
+        assert_eq!(
+            layout.cell_at(inside(3.0, 2.0, 32.0)),
+            Some(CellPos::new(2, 3)),
+            "row 2, column 3"
+        );
+        assert_eq!(
+            layout.cell_at(inside(9.0, 7.0, 32.0)),
+            Some(CellPos::new(7, 9)),
+            "bottom-right"
+        );
+        assert_eq!(
+            layout.cell_at(inside(3.0, 8.0, 32.0)),
+            None,
+            "in the rect, below the last row"
+        );
+        assert_eq!(
+            layout.cell_at(RECT.min - egui::Vec2::splat(1.0)),
+            None,
+            "outside the rect"
+        );
+    }
+
+    #[test]
+    fn finds_cells_at_maximum_zoom_and_after_panning() {
+        let mut view = BoardView::default();
+        view.zoom_at(&view.layout(RECT, 10, 8), RECT.min, 100.0);
+        let layout = view.layout(RECT, 10, 8);
+        assert_eq!(layout.cell_size, egui::Vec2::splat(64.0), "zoom capped");
+        assert_eq!(
+            layout.cell_at(inside(4.0, 1.0, 64.0)),
+            Some(CellPos::new(1, 4)),
+            "row 1, column 4"
+        );
+        assert_eq!(
+            layout.cell_at(inside(5.0, 1.0, 64.0)),
+            None,
+            "cut off by the right edge of the rect"
+        );
+
+        view.pan(egui::vec2(-128.0, -64.0));
+        view.clamp(&view.layout(RECT, 10, 8));
+        let layout = view.layout(RECT, 10, 8);
+        assert_eq!(
+            layout.cell_at(inside(0.0, 0.0, 64.0)),
+            Some(CellPos::new(1, 2)),
+            "two columns and a row scrolled away"
+        );
+        assert_eq!(
+            layout.cell_at(RECT.min - egui::vec2(1.0, -1.0)),
+            None,
+            "left of the rect, over a scrolled-away cell"
+        );
+    }
+}
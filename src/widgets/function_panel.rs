@@ -1,24 +1,157 @@
-pub struct FunctionPanel {
-}
+use crate::{
+    data::{GlobalState, TouchMode},
+    i18n::tr,
+    replay::Replay,
+    scenes::{SceneType, SettingScene},
+    theme::Theme,
+    widgets::BoardView,
+};
 
-impl Default for FunctionPanel {
-    fn default() -> Self {
-        Self::new()
-    }
+/// Where the last replay export was written, or why it was not, kept in the egui memory
+/// under [`FunctionPanel::export_id`].
+type ExportResult = Result<std::path::PathBuf, crate::export::ExportError>;
+
+pub struct FunctionPanel<'a> {
+    global_state: &'a mut GlobalState,
+    replay: &'a Replay,
 }
 
-impl FunctionPanel{
-    pub fn new() -> Self {
-        Self{}
+impl<'a> FunctionPanel<'a> {
+    pub fn new(global_state: &'a mut GlobalState, replay: &'a Replay) -> Self {
+        Self {
+            global_state,
+            replay,
+        }
     }
 }
-impl egui::Widget for FunctionPanel {
-    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+impl egui::Widget for FunctionPanel<'_> {
+    fn ui(mut self, ui: &mut egui::Ui) -> egui::Response {
         egui::Frame::NONE
-            .fill(egui::Color32::BLACK)
+            .fill(Theme::load(ui.ctx()).panel)
             .show(ui, |ui| {
-                ui.label("Function Label")
+                ui.horizontal_centered(|ui| match self.global_state.current_scene {
+                    SceneType::Replay => self.replay_buttons(ui),
+                    SceneType::Setting
+                    | SceneType::Statistics
+                    | SceneType::PlayerStanding
+                    | SceneType::SaveSlots => {
+                        self.back_button(ui);
+                    }
+                    _ => self.game_buttons(ui),
+                });
             })
             .response
     }
 }
+
+impl FunctionPanel<'_> {
+    fn export_id() -> egui::Id {
+        egui::Id::new("REPLAY_EXPORT")
+    }
+    fn game_buttons(&mut self, ui: &mut egui::Ui) {
+        if ui.button(tr("panel.settings")).clicked() {
+            SettingScene::reset_draft(ui.ctx(), &self.global_state.game_settings);
+            self.global_state.change_scene(SceneType::Setting);
+        }
+        if ui
+            .add_enabled(
+                !self.replay.events.is_empty(),
+                egui::Button::new(tr("panel.watch_replay")),
+            )
+            .on_hover_text(tr("panel.watch_replay_hint"))
+            .clicked()
+        {
+            self.global_state.change_scene(SceneType::Replay);
+        }
+        if ui.button(tr("panel.statistics")).clicked() {
+            self.global_state.change_scene(SceneType::Statistics);
+        }
+        if ui.button(tr("panel.best_times")).clicked() {
+            self.global_state.change_scene(SceneType::PlayerStanding);
+        }
+        if ui.button(tr("panel.save_slots")).clicked() {
+            self.global_state.change_scene(SceneType::SaveSlots);
+        }
+        if BoardView::load(ui.ctx()).is_zoomed() && ui.button(tr("panel.fit_board")).clicked() {
+            BoardView::fit(ui.ctx());
+        }
+        let flag_mode = self.global_state.touch_mode == TouchMode::Flag;
+        if ui
+            .selectable_label(flag_mode, tr("panel.flag_mode"))
+            .on_hover_text(tr("panel.flag_mode_hint"))
+            .clicked()
+        {
+            self.global_state.touch_mode = if flag_mode {
+                TouchMode::Reveal
+            } else {
+                TouchMode::Flag
+            };
+        }
+        #[cfg(target_arch = "wasm32")]
+        if ui
+            .button(tr("panel.copy_link"))
+            .on_hover_text(tr("panel.copy_link_hint"))
+            .clicked()
+        {
+            let board = crate::share::SharedBoard::from_replay(self.replay);
+            if let Some(link) = crate::share::publish_board(&board) {
+                ui.ctx().copy_text(link);
+            }
+        }
+    }
+    fn back_button(&mut self, ui: &mut egui::Ui) {
+        if ui.button(tr("panel.back")).clicked() {
+            // The next replay opened starts without the last one's export result.
+            ui.data_mut(|d| d.remove::<ExportResult>(Self::export_id()));
+            self.global_state.change_scene(SceneType::Game);
+        }
+    }
+    fn replay_buttons(&mut self, ui: &mut egui::Ui) {
+        self.back_button(ui);
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            for format in [
+                crate::replay::ReplayFormat::Avf,
+                crate::replay::ReplayFormat::Rawvf,
+            ] {
+                let label = crate::i18n::tr_args(
+                    "panel.export_replay",
+                    &[("extension", &format.extension())],
+                );
+                if ui.button(label).clicked() {
+                    let result = export_replay(self.replay, format);
+                    ui.data_mut(|d| d.insert_temp(Self::export_id(), result));
+                }
+            }
+            match ui.data(|d| d.get_temp::<ExportResult>(Self::export_id())) {
+                Some(Ok(path)) => {
+                    ui.label(tr("panel.exported"))
+                        .on_hover_text(path.display().to_string());
+                }
+                Some(Err(err)) => {
+                    ui.colored_label(ui.visuals().error_fg_color, tr("panel.export_failed"))
+                        .on_hover_text(err.to_string());
+                }
+                None => {}
+            }
+        }
+    }
+}
+
+/// Writes the replay into the export folder.
+#[cfg(not(target_arch = "wasm32"))]
+fn export_replay(
+    replay: &Replay,
+    format: crate::replay::ReplayFormat,
+) -> Result<std::path::PathBuf, crate::export::ExportError> {
+    let data = format
+        .export(replay)
+        .map_err(crate::export::ExportError::Replay)?;
+    let stem = format!("replay-{}", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+    let result = crate::export::write(&stem, format.extension(), &data);
+    match &result {
+        Ok(path) => log::info!("Exported replay to {}", path.display()),
+        Err(err) => log::warn!("Failed to export replay: {err}"),
+    }
+    result
+}
@@ -1,193 +1,1147 @@
+use std::ops::Range;
+
 use crate::{
+    action::GameAction,
     app::GameImageSource,
-    data::{CellData, CellPos, CellRenderState, RoundData, RoundState},
+    bindings::{self, BoundAction, InputBindings},
+    data::{
+        CellData, CellPos, CellRenderState, GameBoardData, GameSettings, GlobalState, RoundData,
+        RoundEndingType, RoundState, TouchMode,
+    },
+    i18n::{tr, tr_args},
+    theme::{Motion, Theme},
+    widgets::{
+        MiniMap,
+        board_animation::{BeforeAction, BoardAnimation, CellPhase},
+        board_view::{BoardLayout, BoardView},
+    },
 };
 
-pub struct Cell {
-    data: CellData,
-    round_state_type: RoundState,
-    image_source: GameImageSource,
+/// What a cell shows: one of the skin's sprites, or the number of a revealed cell, which
+/// the theme may draw itself instead of the skin.
+enum CellSprite<'a> {
+    Image(&'a egui::ImageSource<'static>),
+    /// From 1 to 8.
+    Number(usize),
+}
+
+/// Picks the sprite of one cell for the state of the round.
+struct Cell<'a> {
+    data: &'a CellData,
+    round_state_type: &'a RoundState,
+    image_source: &'a GameImageSource,
+    /// Held down under the pointer, directly or as the neighbour of a pressed number.
+    pressed: bool,
+    /// A number with all its mines flagged and covered neighbours left to chord.
+    chordable: bool,
 }
-impl Cell {
-    fn new(data: CellData, round_state_type: RoundState, image_source: GameImageSource) -> Self {
+impl<'a> Cell<'a> {
+    fn new(
+        data: &'a CellData,
+        round_state_type: &'a RoundState,
+        image_source: &'a GameImageSource,
+    ) -> Self {
         Self {
             data,
             round_state_type,
             image_source,
+            pressed: false,
+            chordable: false,
         }
     }
-}
-
-impl egui::Widget for Cell {
-    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
-        match &self.round_state_type {
-            RoundState::NotStarted => ui.image(self.image_source.cell_closed),
-            RoundState::Playing => match self.data.render_state {
-                CellRenderState::Revealed => {
-                    ui.image(self.image_source.cell_num[self.data.nearby_mines].clone())
-                }
-                CellRenderState::Covered => {
-                    if self.data.is_flagged {
-                        ui.image(self.image_source.cell_flag)
-                    } else {
-                        ui.image(self.image_source.cell_closed)
-                    }
-                }
+    fn pressed(mut self, pressed: bool) -> Self {
+        self.pressed = pressed;
+        self
+    }
+    fn chordable(mut self, chordable: bool) -> Self {
+        self.chordable = chordable;
+        self
+    }
+    fn number_image(&self) -> &'a egui::ImageSource<'static> {
+        let numbers = if self.chordable {
+            &self.image_source.cell_num_yellow
+        } else {
+            &self.image_source.cell_num
+        };
+        numbers
+            .get(self.data.nearby_mines)
+            .unwrap_or(&self.image_source.cell_closed)
+    }
+    fn revealed(&self) -> CellSprite<'a> {
+        match self.data.nearby_mines {
+            0 => CellSprite::Image(self.empty_image()),
+            number => CellSprite::Number(number),
+        }
+    }
+    /// A revealed cell with no number, which also stands for a pressed covered cell.
+    fn empty_image(&self) -> &'a egui::ImageSource<'static> {
+        self.image_source
+            .cell_num
+            .first()
+            .unwrap_or(&self.image_source.cell_closed)
+    }
+    fn sprite(&self) -> CellSprite<'a> {
+        let images = self.image_source;
+        let image = match self.round_state_type {
+            RoundState::NotStarted | RoundState::Playing => match self.data.render_state {
+                CellRenderState::Revealed => return self.revealed(),
+                CellRenderState::Covered if self.data.is_flagged => &images.cell_flag,
+                CellRenderState::Covered if self.pressed => self.empty_image(),
+                CellRenderState::Covered => &images.cell_closed,
             },
-            RoundState::Ended(round_ending_type) => match round_ending_type.clone() {
-                crate::data::RoundEndingType::ClickedMine(cell_pos) => {
-                    if !self.data.is_mine {
-                        ui.image(self.image_source.cell_num[self.data.nearby_mines].clone())
-                    } else if cell_pos == self.data.position {
-                        ui.image(self.image_source.cell_mine_red)
-                    } else {
-                        ui.image(self.image_source.cell_mine)
-                    }
+            // Post-mortem: every mine is shown, flags on mines stay, wrong flags are
+            // crossed out and the rest of the board is left as it was.
+            RoundState::Ended(RoundEndingType::ClickedMine(cell_pos)) => {
+                match (self.data.is_mine, self.data.is_flagged) {
+                    _ if *cell_pos == self.data.position => &images.cell_mine_red,
+                    (true, true) => &images.cell_flag,
+                    (true, false) => &images.cell_mine,
+                    (false, true) => &images.cell_mine_wrong,
+                    (false, false) => match self.data.render_state {
+                        CellRenderState::Revealed => return self.revealed(),
+                        CellRenderState::Covered => &images.cell_closed,
+                    },
                 }
-                crate::data::RoundEndingType::Victory => {
-                    if self.data.is_mine {
-                        ui.image(self.image_source.cell_mine)
-                    } else {
-                        ui.image(self.image_source.cell_num[self.data.nearby_mines].clone())
-                    }
+            }
+            RoundState::Ended(RoundEndingType::Victory) => {
+                if self.data.is_mine {
+                    &images.cell_mine
+                } else {
+                    return self.revealed();
                 }
-            },
+            }
+        };
+        CellSprite::Image(image)
+    }
+    fn paint(&self, ui: &egui::Ui, rect: egui::Rect, theme: &Theme) {
+        match (self.sprite(), theme.number_colors) {
+            (CellSprite::Number(number), Some(colors)) => {
+                self.paint_number(ui, rect, theme, number, &colors);
+            }
+            (CellSprite::Number(_), None) => paint_image(ui, self.number_image(), rect, theme),
+            (CellSprite::Image(image), _) => paint_image(ui, image, rect, theme),
+        }
+    }
+    /// Draws a number over the empty sprite in the theme's colours, rather than with the
+    /// skin's number sprites.
+    fn paint_number(
+        &self,
+        ui: &egui::Ui,
+        rect: egui::Rect,
+        theme: &Theme,
+        number: usize,
+        colors: &[egui::Color32; 8],
+    ) {
+        paint_image(ui, self.empty_image(), rect, theme);
+        let painter = ui.painter();
+        if self.chordable {
+            // The light face of the classic sprites, coloured like the yellow numbers.
+            painter.rect_filled(
+                rect.shrink(rect.width() * 0.061),
+                0.0,
+                egui::Color32::from_rgb(224, 224, 128) * theme.cell_tint,
+            );
         }
+        let color = colors.get(number - 1).copied().unwrap_or_default() * theme.cell_tint;
+        if theme.shape_cues {
+            paint_shape_cue(painter, rect, number, color.gamma_multiply(0.45));
+        }
+        painter.text(
+            rect.center(),
+            egui::Align2::CENTER_CENTER,
+            number.to_string(),
+            egui::FontId::monospace(rect.height() * 0.75),
+            color,
+        );
     }
 }
+
+fn paint_image(ui: &egui::Ui, image: &egui::ImageSource<'static>, rect: egui::Rect, theme: &Theme) {
+    egui::Image::new(image.clone())
+        .tint(theme.cell_tint)
+        .paint_at(ui, rect);
+}
+
+/// Outlines a shape behind a number, a different one for each so numbers can be told
+/// apart without their colour: a circle for 1, then a diamond, a triangle, a square, a
+/// pentagon, a hexagon, a star and a cross for 8.
+fn paint_shape_cue(painter: &egui::Painter, rect: egui::Rect, number: usize, color: egui::Color32) {
+    let center = rect.center();
+    let radius = rect.width() * 0.4;
+    let stroke = egui::Stroke::new((rect.width() * 0.06).max(1.0), color);
+    // Corners from the top, clockwise, alternating between `radii` for the star.
+    let polygon = |corners: usize, radii: &[f32], turn: f32| -> Vec<egui::Pos2> {
+        (0..corners)
+            .map(|corner| {
+                let angle = std::f32::consts::TAU * corner as f32 / corners as f32 + turn;
+                let scale = radii.get(corner % radii.len()).copied().unwrap_or(1.0);
+                center + radius * scale * egui::vec2(angle.sin(), -angle.cos())
+            })
+            .collect()
+    };
+    let points = match number {
+        1 => {
+            painter.circle_stroke(center, radius, stroke);
+            return;
+        }
+        2 => polygon(4, &[1.0], 0.0),
+        3 => polygon(3, &[1.0], 0.0),
+        4 => polygon(4, &[1.0], std::f32::consts::FRAC_PI_4),
+        5 => polygon(5, &[1.0], 0.0),
+        6 => polygon(6, &[1.0], 0.0),
+        7 => polygon(10, &[1.0, 0.45], 0.0),
+        _ => {
+            for arm in [egui::vec2(radius, 0.0), egui::vec2(0.0, radius)] {
+                painter.line_segment([center - arm, center + arm], stroke);
+            }
+            return;
+        }
+    };
+    painter.add(egui::Shape::closed_line(points, stroke));
+}
+
 pub struct GameBoard<'a> {
     round_state: &'a mut RoundData,
     rect: egui::Rect,
+    interactive: bool,
+    /// Zoom and pan, only for interactive boards; replays always fit their rect.
+    view: BoardView,
 }
 impl<'a> GameBoard<'a> {
     pub fn new(round_state: &'a mut RoundData, rect: egui::Rect) -> Self {
-        Self { round_state, rect }
+        Self {
+            round_state,
+            rect,
+            interactive: true,
+            view: BoardView::default(),
+        }
+    }
+    /// Whether clicks on the board are turned into game actions. Disabled for replays.
+    pub fn interactive(mut self, interactive: bool) -> Self {
+        self.interactive = interactive;
+        self
     }
 }
 impl egui::Widget for GameBoard<'_> {
     fn ui(mut self, ui: &mut egui::Ui) -> egui::Response {
-
         #[cfg(debug_assertions)]
-        if self.round_state.round_state_type == RoundState::Playing {
+        if self.interactive && self.round_state.round_state_type == RoundState::Playing {
             self.round_state.solve_for_me();
         }
 
-        let image_source: crate::app::GameImageSource =
-            ui.data(|d| d.get_temp(egui::Id::new("IMAGE_SOURCE")).unwrap());
+        let image_source: crate::app::GameImageSource = ui.data(|d| {
+            d.get_temp(egui::Id::new("IMAGE_SOURCE"))
+                .unwrap_or_default()
+        });
 
-        let cell_size = egui::vec2(
-            self.rect.width() / self.round_state.board_size as f32,
-            self.rect.width() / self.round_state.board_size as f32,
-        );
+        let before =
+            (self.interactive && may_act(ui)).then(|| BeforeAction::take(self.round_state));
+        let response = if self.interactive {
+            self.view = BoardView::load(ui.ctx());
+            self.handle_view_input(ui)
+        } else {
+            ui.allocate_rect(self.rect, egui::Sense::hover())
+        };
+        let input = BoardInput::read(ui, self.interactive);
+        let (settings, bindings) = (&input.settings, &input.bindings);
+        let mut cursor = if self.interactive {
+            self.handle_keyboard(ui, bindings, settings)
+        } else {
+            None
+        };
+        let layout = self.layout();
+        if self.interactive {
+            if self.round_state.round_state_type == RoundState::Playing {
+                self.record_cursor(ui, &layout);
+            }
+            let hit = response
+                .interact_pointer_pos()
+                .and_then(|it| layout.cell_at(it));
+            if let Some(pos) = hit {
+                self.handle_cell_input(&response, &pos, &input);
+            }
+        }
+        self.describe_cells(ui, &response, &layout, &mut cursor, settings);
+        if let Some(before) = &before {
+            self.animate(ui, before);
+        }
 
-        egui::Grid::new("GameBoard")
-            .show(ui, |ui| {
-                for j in 0..self.round_state.board_size {
-                    for k in 0..self.round_state.board_size {
-                        // ui.add_sized(cell_size, egui::Label::new("11"));
-                        ui.add_sized(
-                            cell_size,
-                            Cell::new(
-                                self.round_state.board_data.cells[j][k].clone(),
-                                self.round_state.round_state_type.clone(),
-                                image_source.clone(),
-                            ),
-                            // egui::Label::new(format!("({},{})",j,k))
-                        );
-                        let response = ui.interact(
-                            egui::Rect::from_min_size(
-                                egui::pos2(
-                                    self.rect.min.x + cell_size.x * k as f32,
-                                    self.rect.min.y + cell_size.y * j as f32,
-                                ),
-                                cell_size,
-                            ),
-                            egui::Id::from(format!("{j},{k}")),
-                            egui::Sense::click(),
-                        );
-                        if (self.round_state.round_state_type == RoundState::NotStarted)
-                            || self.round_state.round_state_type == RoundState::Playing
-                        {
-                            if response.clicked_by(egui::PointerButton::Primary) {
-                                self.handle_left_click(&CellPos::new(j, k));
-                            }
-                            if response.clicked_by(egui::PointerButton::Secondary) {
-                                self.handle_right_click(&CellPos::new(j, k));
-                            }
-                        }
-                    }
-                    ui.end_row();
-                }
+        // Cells panned out of the rect are clipped.
+        ui.set_clip_rect(self.rect.intersect(ui.clip_rect()));
+        self.paint_cells(ui, &layout, &image_source, bindings.reveal_button());
+        if self.round_state.is_paused() {
+            paint_paused(ui, self.rect, &Theme::load(ui.ctx()));
+        } else if let Some(cursor) = &cursor {
+            paint_cursor(ui, &layout, cursor);
+        }
+        if input.touch.touch && !input.touch.gesture {
+            self.paint_long_press_ring(ui, &layout);
+        }
+        if self.interactive {
+            if self.view.is_cropped() && !self.round_state.is_paused() {
+                self.show_minimap(ui, &layout);
+            }
+            self.view.store(ui.ctx());
+        }
+        response
+    }
+}
+/// Whether the input of this frame could act on the board, which is only worth looking at
+/// the board before on such frames.
+fn may_act(ui: &egui::Ui) -> bool {
+    ui.input(|i| {
+        i.pointer.any_released()
+            || i.events.iter().any(|it| {
+                matches!(
+                    it,
+                    egui::Event::Key { pressed: true, .. } | egui::Event::AccessKitActionRequest(_)
+                )
             })
-            .response
+    })
+}
+
+/// Whether chording on `cell` would reveal something: a number whose mines are all flagged,
+/// next to covered cells.
+fn is_chordable(board_data: &GameBoardData, cell: &CellData) -> bool {
+    if cell.render_state != CellRenderState::Revealed || cell.nearby_mines == 0 {
+        return false;
+    }
+    let (mut flags, mut covered) = (0, false);
+    for pos in board_data.get_surround_positions(&cell.position) {
+        if let Some(it) = board_data.get_cell(&pos) {
+            flags += usize::from(it.is_flagged);
+            covered |= it.render_state == CellRenderState::Covered && !it.is_flagged;
+        }
     }
+    flags == cell.nearby_mines && covered
+}
+
+/// Rows and columns of the cells at least partly in view.
+fn visible_ranges(
+    layout: &BoardLayout,
+    board_data: &GameBoardData,
+) -> (Range<usize>, Range<usize>) {
+    let visible = layout.visible_cells();
+    let rows = visible.min.y.max(0.0) as usize..(visible.max.y.ceil().max(0.0) as usize);
+    let columns = visible.min.x.max(0.0) as usize..(visible.max.x.ceil().max(0.0) as usize);
+    (
+        rows.start..rows.end.min(board_data.height()),
+        columns.start..columns.end.min(board_data.width()),
+    )
 }
+
+/// What a screen reader says for a cell, matching what its sprite shows.
+fn spoken_state(data: &CellData, round_state: &RoundState) -> String {
+    let revealed = || match data.nearby_mines {
+        0 => tr("cell.empty").to_owned(),
+        number => tr_args("cell.number", &[("number", &number)]),
+    };
+    let spoken = match round_state {
+        RoundState::NotStarted | RoundState::Playing => match data.render_state {
+            CellRenderState::Revealed => return revealed(),
+            CellRenderState::Covered if data.is_flagged => "cell.flagged",
+            CellRenderState::Covered => "cell.covered",
+        },
+        RoundState::Ended(RoundEndingType::ClickedMine(cell_pos)) => {
+            match (data.is_mine, data.is_flagged) {
+                _ if *cell_pos == data.position => "cell.exploded_mine",
+                (true, true) => "cell.flagged",
+                (true, false) => "cell.mine",
+                (false, true) => "cell.wrong_flag",
+                (false, false) => match data.render_state {
+                    CellRenderState::Revealed => return revealed(),
+                    CellRenderState::Covered => "cell.covered",
+                },
+            }
+        }
+        RoundState::Ended(RoundEndingType::Victory) => {
+            if data.is_mine {
+                "cell.mine"
+            } else {
+                return revealed();
+            }
+        }
+    };
+    tr(spoken).to_owned()
+}
+
+/// Outlines the keyboard cursor so it stands out on both covered and revealed cells.
+fn paint_cursor(ui: &egui::Ui, layout: &BoardLayout, cursor: &CellPos) {
+    let theme = Theme::load(ui.ctx());
+    ui.painter().rect_stroke(
+        layout.cell_rect(cursor.x, cursor.y),
+        0.0,
+        egui::Stroke::new(theme.outline_width(), theme.highlight),
+        egui::StrokeKind::Inside,
+    );
+}
+
+/// Hides the board while the round is paused, so the clock cannot be beaten by thinking.
+fn paint_paused(ui: &egui::Ui, board_rect: egui::Rect, theme: &Theme) {
+    ui.painter().rect_filled(board_rect, 0.0, theme.paused);
+    ui.painter().text(
+        board_rect.center(),
+        egui::Align2::CENTER_CENTER,
+        tr("board.paused"),
+        egui::FontId::proportional(board_rect.width() / 10.0),
+        theme.paused_text,
+    );
+}
+
+/// Moves `value` by `step` within `0..len`, or to the edge in that direction when `to_edge`.
+fn step_within(value: usize, step: isize, len: usize, to_edge: bool) -> usize {
+    let last = len.saturating_sub(1);
+    match (step.signum(), to_edge) {
+        (0, _) => value.min(last),
+        (-1, true) => 0,
+        (_, true) => last,
+        _ => value.saturating_add_signed(step).min(last),
+    }
+}
+
+/// What the current press on a touch screen has been so far.
+#[derive(Clone, Copy, Debug, Default)]
+struct TouchPress {
+    /// Made with a finger, so taps follow the [`TouchMode`] rather than the bindings.
+    touch: bool,
+    /// Became a pinch or other multi-finger gesture, so its taps are ignored.
+    gesture: bool,
+}
+
+/// Follows the press under way, which outlives the touches themselves by a frame since
+/// the release is only seen once the finger has lifted.
+fn track_touch(ui: &egui::Ui) -> TouchPress {
+    let id = egui::Id::new("TOUCH_PRESS");
+    let (touching, multi_touch, pressing) = ui.input(|i| {
+        (
+            i.any_touches(),
+            i.multi_touch().is_some(),
+            i.pointer.any_down() || i.pointer.any_released(),
+        )
+    });
+    let mut press: TouchPress = if touching || pressing {
+        ui.data(|d| d.get_temp(id)).unwrap_or_default()
+    } else {
+        TouchPress::default()
+    };
+    press.touch |= touching;
+    press.gesture |= multi_touch;
+    ui.data_mut(|d| d.insert_temp(id, press));
+    press
+}
+
+/// Everything that decides what a click or tap on the board does.
+struct BoardInput {
+    settings: GameSettings,
+    touch_mode: TouchMode,
+    touch: TouchPress,
+    bindings: InputBindings,
+    modifiers: egui::Modifiers,
+}
+
+impl BoardInput {
+    fn read(ui: &egui::Ui, interactive: bool) -> Self {
+        let (settings, touch_mode) = ui
+            .data(|d| d.get_temp::<GlobalState>(egui::Id::NULL))
+            .map(|it| (it.game_settings, it.touch_mode))
+            .unwrap_or_default();
+        Self {
+            settings,
+            touch_mode,
+            touch: if interactive {
+                track_touch(ui)
+            } else {
+                TouchPress::default()
+            },
+            bindings: ui
+                .data(|d| d.get_temp(egui::Id::new("INPUT_BINDINGS")))
+                .unwrap_or_default(),
+            modifiers: ui.input(|i| i.modifiers),
+        }
+    }
+}
+
+/// Every mouse button a binding can use.
+const POINTER_BUTTONS: [egui::PointerButton; 5] = [
+    egui::PointerButton::Primary,
+    egui::PointerButton::Secondary,
+    egui::PointerButton::Middle,
+    egui::PointerButton::Extra1,
+    egui::PointerButton::Extra2,
+];
+
 impl GameBoard<'_> {
-    fn handle_right_click(&mut self, pos: &CellPos) {
+    fn layout(&self) -> BoardLayout {
+        self.view.layout(
+            self.rect,
+            self.round_state.board_width,
+            self.round_state.board_height,
+        )
+    }
+    /// Hit-tests the whole board at once: wheel or pinch zooms around the pointer, and
+    /// dragging pans. A drag never counts as a click, so panning does not reveal. Returns
+    /// the board's response, whose clicks land on the cell under the pointer.
+    fn handle_view_input(&mut self, ui: &egui::Ui) -> egui::Response {
+        let response = ui.interact(
+            self.rect,
+            egui::Id::new("GAME_BOARD"),
+            egui::Sense::click_and_drag(),
+        );
+        // Arrows move the cursor rather than focus while the board has focus, as it does
+        // for screen readers.
+        ui.memory_mut(|m| {
+            m.set_focus_lock_filter(
+                response.id,
+                egui::EventFilter {
+                    horizontal_arrows: true,
+                    vertical_arrows: true,
+                    ..Default::default()
+                },
+            );
+        });
+        let (zoom, scroll, center) = ui.input(|i| {
+            (
+                i.zoom_delta(),
+                i.smooth_scroll_delta.y,
+                i.multi_touch()
+                    .map(|it| it.center_pos)
+                    .or_else(|| i.pointer.hover_pos()),
+            )
+        });
+        let factor = zoom * (scroll / 200.0).exp();
+        if let Some(center) = center.filter(|it| self.rect.contains(*it)) {
+            if (factor - 1.0).abs() > f32::EPSILON {
+                let layout = self.layout();
+                self.view.zoom_at(&layout, center, factor);
+            }
+        }
+        self.view.pan(response.drag_delta());
+        let layout = self.layout();
+        self.view.clamp(&layout);
+        response
+    }
+    /// Paints the cells in view, and only those, straight onto the board's painter.
+    fn paint_cells(
+        &self,
+        ui: &egui::Ui,
+        layout: &BoardLayout,
+        image_source: &GameImageSource,
+        reveal_button: Option<egui::PointerButton>,
+    ) {
+        let pressed = if self.interactive {
+            self.pressed_cells(ui, layout, reveal_button)
+        } else {
+            vec![]
+        };
+        let round_state_type = &self.round_state.round_state_type;
+        let in_progress = matches!(
+            round_state_type,
+            RoundState::NotStarted | RoundState::Playing
+        );
         let board_data = &self.round_state.board_data;
-        let mut cell = board_data.cells[pos.x][pos.y].clone();
-        if cell.render_state == CellRenderState::Covered {
-            cell.is_flagged = !cell.is_flagged;
-            self.round_state.board_data.update_cells(vec![cell]);
+        let theme = Theme::load(ui.ctx());
+        let animation =
+            BoardAnimation::load(ui.ctx()).filter(|_| *round_state_type != RoundState::NotStarted);
+        let now = ui.input(|i| i.time);
+        let (rows, columns) = visible_ranges(layout, board_data);
+        for row in board_data.cells.get(rows).unwrap_or_default() {
+            for cell in row.get(columns.clone()).unwrap_or_default() {
+                let (x, y) = (cell.position.x, cell.position.y);
+                let rect = layout.cell_rect(x, y);
+                let sprite = Cell::new(cell, round_state_type, image_source)
+                    .pressed(pressed.contains(&cell.position))
+                    .chordable(in_progress && is_chordable(board_data, cell));
+                let Some(animation) = &animation else {
+                    sprite.paint(ui, rect, &theme);
+                    continue;
+                };
+                match animation.phase(x, y, now) {
+                    CellPhase::Settled => sprite.paint(ui, rect, &theme),
+                    CellPhase::Before => {
+                        let covered = CellData {
+                            render_state: CellRenderState::Covered,
+                            ..cell.clone()
+                        };
+                        Cell::new(&covered, &RoundState::Playing, image_source)
+                            .paint(ui, rect, &theme);
+                    }
+                    CellPhase::Showing(progress) => {
+                        paint_image(ui, sprite.empty_image(), rect, &theme);
+                        sprite.paint(ui, rect.scale_from_center(0.5 + 0.5 * progress), &theme);
+                    }
+                }
+                let glow = animation.glow(x, y, now);
+                if glow > 0.0 {
+                    ui.painter().rect_filled(
+                        rect,
+                        0.0,
+                        theme.celebration.gamma_multiply(0.6 * glow),
+                    );
+                }
+            }
+        }
+        if let Some(animation) = &animation {
+            if let Some(pos) = animation.exploded() {
+                let rect = layout.cell_rect(pos.x, pos.y);
+                animation.paint_explosion(ui.painter(), rect, &theme, now);
+            }
+            ui.ctx().request_repaint();
+        }
+    }
+    /// Sets off the animation for what the input of this frame did to the round, and ends
+    /// the one under way when the round was restarted or an action undone.
+    fn animate(&self, ui: &egui::Ui, before: &BeforeAction) {
+        let now = ui.input(|i| i.time);
+        let motion = Motion::load(ui.ctx());
+        match BoardAnimation::after(before, self.round_state, motion, now) {
+            Some(animation) => BoardAnimation::store(ui.ctx(), Some(animation)),
+            None if before.events() > self.round_state.replay.events.len() => {
+                BoardAnimation::store(ui.ctx(), None);
+            }
+            None => {}
+        }
+    }
+    /// Lays the cells in view out for screen readers while AccessKit is on: the board is a
+    /// grid of rows of cells, each spoken as "covered", "flagged", its number and so on,
+    /// with the keyboard cursor as the active cell. Moving a screen reader onto a cell puts
+    /// the cursor there, and activating the cell reveals it.
+    fn describe_cells(
+        &mut self,
+        ui: &mut egui::Ui,
+        board: &egui::Response,
+        layout: &BoardLayout,
+        cursor: &mut Option<CellPos>,
+        settings: &GameSettings,
+    ) {
+        use egui::accesskit::{Action, NodeId, Role};
+        let cell_id = |pos: &CellPos| board.id.with(("cell", pos.x, pos.y));
+        let active = cursor.as_ref().map(|it| NodeId(cell_id(it).value()));
+        let described = ui.ctx().accesskit_node_builder(board.id, |node| {
+            node.set_role(Role::Grid);
+            node.set_label(tr("board.label"));
+            node.set_row_count(self.round_state.board_height);
+            node.set_column_count(self.round_state.board_width);
+            if let Some(active) = active {
+                node.set_active_descendant(active);
+            }
+        });
+        let (rows, columns) = visible_ranges(layout, &self.round_state.board_data);
+        if described.is_none() || columns.is_empty() {
+            return;
+        }
+        let mut request = None;
+        for row in rows {
+            let row_rect = layout
+                .cell_rect(row, columns.start)
+                .union(layout.cell_rect(row, columns.end - 1));
+            let row_ui = ui.new_child(
+                egui::UiBuilder::new()
+                    .id_salt(("board_row", row))
+                    .max_rect(row_rect)
+                    .accessibility_parent(board.id),
+            );
+            ui.ctx().accesskit_node_builder(row_ui.unique_id(), |node| {
+                node.set_role(Role::Row);
+                node.set_row_index(row);
+            });
+            for column in columns.clone() {
+                let pos = CellPos::new(row, column);
+                let Some(data) = self.round_state.board_data.get_cell(&pos) else {
+                    continue;
+                };
+                let id = cell_id(&pos);
+                row_ui.interact(layout.cell_rect(row, column), id, egui::Sense::hover());
+                ui.ctx().accesskit_node_builder(id, |node| {
+                    node.set_role(Role::Cell);
+                    node.set_row_index(row);
+                    node.set_column_index(column);
+                    node.set_label(spoken_state(data, &self.round_state.round_state_type));
+                    node.set_selected(cursor.as_ref() == Some(&pos));
+                    if self.interactive {
+                        node.add_action(Action::Focus);
+                        node.add_action(Action::Click);
+                    }
+                });
+                for action in [Action::Focus, Action::Click] {
+                    if self.interactive && ui.input(|i| i.has_accesskit_action_request(id, action))
+                    {
+                        request = Some((pos.clone(), action));
+                    }
+                }
+            }
+        }
+        let Some((pos, action)) = request else {
+            return;
+        };
+        *cursor = Some(pos.clone());
+        ui.data_mut(|d| d.insert_temp(egui::Id::new("BOARD_CURSOR"), cursor.clone()));
+        board.request_focus();
+        if action == Action::Click {
+            self.perform_bound(BoundAction::Reveal, Some(&pos), settings);
+        }
+    }
+    /// A thumbnail in the corner while zoomed in, where clicking or dragging moves the view.
+    fn show_minimap(&mut self, ui: &mut egui::Ui, layout: &BoardLayout) {
+        let side = self.rect.width().min(self.rect.height()) / 4.0;
+        let board = egui::vec2(
+            self.round_state.board_width.max(1) as f32,
+            self.round_state.board_height.max(1) as f32,
+        );
+        let size = board * side / board.max_elem();
+        let rect = egui::Rect::from_min_size(self.rect.max - size - egui::vec2(8.0, 8.0), size);
+        let response = ui.put(
+            rect,
+            MiniMap::new(&self.round_state.board_data, side).viewport(layout.visible_cells()),
+        );
+        if let Some(pointer) = response.interact_pointer_pos() {
+            let cells = (pointer - response.rect.min) / response.rect.size() * board;
+            self.view.center_on(layout, cells.x, cells.y);
+            self.view.clamp(layout);
         }
     }
-    fn handle_left_click(&mut self, pos: &CellPos) {
+    /// Carries out a bound action. Cell actions need `pos` and a round in play; round
+    /// actions ignore it.
+    fn perform_bound(
+        &mut self,
+        action: BoundAction,
+        pos: Option<&CellPos>,
+        settings: &GameSettings,
+    ) {
+        let in_progress = matches!(
+            self.round_state.round_state_type,
+            RoundState::NotStarted | RoundState::Playing
+        ) && !self.round_state.is_paused();
+        match (action, pos) {
+            (BoundAction::Restart, _) => *self.round_state = settings.new_round(),
+            (BoundAction::Hint, _) => {
+                self.round_state.hint();
+            }
+            (BoundAction::Undo, _) => {
+                self.round_state.undo();
+            }
+            (BoundAction::Pause, _) => self.round_state.toggle_pause(),
+            (BoundAction::Reveal | BoundAction::Flag | BoundAction::Chord, None) => {}
+            (_, Some(_)) if !in_progress => {}
+            (BoundAction::Reveal, Some(pos)) => self.handle_left_click(pos, settings),
+            (BoundAction::Flag, Some(pos)) => self.handle_right_click(pos),
+            (BoundAction::Chord, Some(pos)) => {
+                self.round_state.perform(GameAction::Chord(pos.clone()));
+            }
+        }
+    }
+    /// Fills a ring around a finger held still on a covered cell, which is flagged or
+    /// unflagged once the ring closes.
+    fn paint_long_press_ring(&self, ui: &egui::Ui, layout: &BoardLayout) {
+        let long_press = ui.ctx().options(|o| o.input_options.max_click_duration);
+        let state = ui.input(|i| {
+            let held_for = i.time - i.pointer.press_start_time()?;
+            let origin = i.pointer.press_origin()?;
+            i.pointer
+                .could_any_button_be_click()
+                .then_some((origin, held_for))
+        });
+        let Some((origin, held_for)) = state else {
+            return;
+        };
+        let covered = layout
+            .cell_at(origin)
+            .and_then(|it| self.round_state.board_data.get_cell(&it))
+            .is_some_and(|it| it.render_state == CellRenderState::Covered);
+        let in_progress = matches!(
+            self.round_state.round_state_type,
+            RoundState::NotStarted | RoundState::Playing
+        ) && !self.round_state.is_paused();
+        if !covered || !in_progress {
+            return;
+        }
+        let progress = (held_for / long_press).clamp(0.0, 1.0) as f32;
+        let radius = layout.cell_size.x.max(24.0);
+        let points: Vec<egui::Pos2> = (0..=64)
+            .map(|step| {
+                let angle = std::f32::consts::TAU * progress * step as f32 / 64.0;
+                origin + radius * egui::vec2(angle.sin(), -angle.cos())
+            })
+            .collect();
+        ui.painter().add(egui::Shape::line(
+            points,
+            egui::Stroke::new(4.0, Theme::load(ui.ctx()).long_press),
+        ));
+        if progress < 1.0 {
+            ui.ctx().request_repaint();
+        }
+    }
+    /// Taps follow the touch controls and clicks the bindings.
+    fn handle_cell_input(&mut self, response: &egui::Response, pos: &CellPos, input: &BoardInput) {
+        if input.touch.touch {
+            if !input.touch.gesture {
+                self.handle_tap(response, pos, input.touch_mode, &input.settings);
+            }
+            return;
+        }
+        let action = POINTER_BUTTONS
+            .into_iter()
+            .filter(|it| response.clicked_by(*it))
+            .find_map(|it| input.bindings.pointer_action(it, input.modifiers));
+        if let Some(action) = action {
+            self.perform_bound(action, Some(pos), &input.settings);
+        }
+    }
+    /// Touch controls: a tap reveals or flags a covered cell depending on `touch_mode`, a
+    /// tap on a number chords, and a long press flags.
+    fn handle_tap(
+        &mut self,
+        response: &egui::Response,
+        pos: &CellPos,
+        touch_mode: TouchMode,
+        settings: &GameSettings,
+    ) {
+        let in_progress = matches!(
+            self.round_state.round_state_type,
+            RoundState::NotStarted | RoundState::Playing
+        ) && !self.round_state.is_paused();
+        if !in_progress {
+            return;
+        }
+        let Some(cell) = self.round_state.board_data.get_cell(pos) else {
+            return;
+        };
+        let covered = cell.render_state == CellRenderState::Covered;
+        if response.long_touched() {
+            if covered {
+                self.handle_right_click(pos);
+            }
+        } else if response.clicked() {
+            match (covered, touch_mode) {
+                (false, _) => self.round_state.perform(GameAction::Chord(pos.clone())),
+                (true, TouchMode::Reveal) => self.handle_left_click(pos, settings),
+                (true, TouchMode::Flag) => self.handle_right_click(pos),
+            }
+        }
+    }
+    /// Plays with the keyboard while no widget but the board has focus, so text fields and
+    /// focused buttons keep their keys. Arrows, WASD or hjkl move the cursor, with Shift to jump to
+    /// the edge, and bound keys act on the cell under it. Returns the cursor to outline,
+    /// which hides again once the board is clicked.
+    fn handle_keyboard(
+        &mut self,
+        ui: &egui::Ui,
+        bindings: &InputBindings,
+        settings: &GameSettings,
+    ) -> Option<CellPos> {
+        let cursor_id = egui::Id::new("BOARD_CURSOR");
+        let (width, height) = (self.round_state.board_width, self.round_state.board_height);
+        // Kept on the board when a new round is smaller than the last.
+        let mut cursor = ui
+            .data(|d| d.get_temp::<Option<CellPos>>(cursor_id))
+            .flatten()
+            .map(|it| {
+                CellPos::new(
+                    it.x.min(height.saturating_sub(1)),
+                    it.y.min(width.saturating_sub(1)),
+                )
+            });
+        let clicked_board = ui.input(|i| {
+            i.pointer.any_click()
+                && i.pointer
+                    .interact_pos()
+                    .is_some_and(|it| self.rect.contains(it))
+        });
+        if clicked_board {
+            cursor = None;
+        }
+        if ui.memory(|m| {
+            m.focused()
+                .is_some_and(|it| it != egui::Id::new("GAME_BOARD"))
+        }) {
+            ui.data_mut(|d| d.insert_temp(cursor_id, cursor.clone()));
+            return cursor;
+        }
+
+        let keys: Vec<(egui::Key, egui::Modifiers, bool)> = ui.input(|i| {
+            i.events
+                .iter()
+                .filter_map(|event| match event {
+                    egui::Event::Key {
+                        key,
+                        pressed: true,
+                        modifiers,
+                        repeat,
+                        ..
+                    } => Some((*key, *modifiers, *repeat)),
+                    _ => None,
+                })
+                .collect()
+        });
+        let before = cursor.clone();
+        // The first key for the cursor only brings it up, in the middle of the board.
+        let middle = CellPos::new(height / 2, width / 2);
+        for (key, modifiers, repeat) in keys {
+            let jump = modifiers == egui::Modifiers::SHIFT;
+            if let Some((row_step, column_step)) =
+                bindings::movement(key).filter(|_| jump || modifiers.is_none())
+            {
+                cursor = Some(cursor.map_or(middle.clone(), |pos| {
+                    CellPos::new(
+                        step_within(pos.x, row_step, height, jump),
+                        step_within(pos.y, column_step, width, jump),
+                    )
+                }));
+                continue;
+            }
+            let Some(action) = bindings.key_action(key, modifiers).filter(|_| !repeat) else {
+                continue;
+            };
+            if action.targets_cell() && cursor.is_none() {
+                cursor = Some(middle.clone());
+                continue;
+            }
+            self.perform_bound(action, cursor.as_ref(), settings);
+        }
+        if let Some(pos) = cursor.as_ref().filter(|_| cursor != before) {
+            let layout = self.layout();
+            self.view.scroll_to(&layout, pos);
+        }
+
+        ui.data_mut(|d| d.insert_temp(cursor_id, cursor.clone()));
+        cursor
+    }
+    /// Cells drawn held down while the reveal button is pressed over the board: the cell
+    /// under the pointer if covered, or the covered neighbours of a number.
+    fn pressed_cells(
+        &self,
+        ui: &egui::Ui,
+        layout: &BoardLayout,
+        reveal_button: Option<egui::PointerButton>,
+    ) -> Vec<CellPos> {
+        if !matches!(
+            self.round_state.round_state_type,
+            RoundState::NotStarted | RoundState::Playing
+        ) {
+            return vec![];
+        }
+        let pointer = ui.input(|i| {
+            (reveal_button.is_some_and(|it| i.pointer.button_down(it))
+                && !i.pointer.is_decidedly_dragging())
+            .then(|| i.pointer.hover_pos())
+            .flatten()
+        });
+        let Some(pos) = pointer.and_then(|it| layout.cell_at(it)) else {
+            return vec![];
+        };
         let board_data = &self.round_state.board_data;
-        let origin_cell = &board_data.cells[pos.x][pos.y];
+        let Some(cell) = board_data.get_cell(&pos) else {
+            return vec![];
+        };
+        let candidates = match cell.render_state {
+            CellRenderState::Covered => vec![pos],
+            CellRenderState::Revealed => board_data.get_surround_positions(&pos),
+        };
+        candidates
+            .into_iter()
+            .filter(|it| {
+                board_data
+                    .get_cell(it)
+                    .is_some_and(|it| it.render_state == CellRenderState::Covered && !it.is_flagged)
+            })
+            .collect()
+    }
+    fn record_cursor(&mut self, ui: &egui::Ui, layout: &BoardLayout) {
+        if let Some(pointer) = ui.ctx().pointer_hover_pos() {
+            if self.rect.contains(pointer) {
+                let cells = layout.position_in_cells(pointer);
+                let time_ms = self.round_state.elapsed_ms();
+                self.round_state
+                    .replay
+                    .record_cursor(time_ms, cells.x, cells.y);
+            }
+        }
+    }
+    fn handle_right_click(&mut self, pos: &CellPos) {
+        self.round_state
+            .perform(GameAction::ToggleFlag(pos.clone()));
+    }
+    fn handle_left_click(&mut self, pos: &CellPos, settings: &GameSettings) {
+        if settings.safe_first_click() {
+            self.round_state.make_safe_opening(pos);
+        }
+        let board_data = &self.round_state.board_data;
+        let Some(origin_cell) = board_data.get_cell(pos) else {
+            return;
+        };
         match origin_cell.render_state {
             CellRenderState::Covered => {
-                self.reveal_cell(pos);
+                self.round_state.perform(GameAction::Reveal(pos.clone()));
+            }
+            CellRenderState::Revealed if settings.single_click_chord() => {
+                self.round_state.perform(GameAction::Chord(pos.clone()));
             }
             CellRenderState::Revealed => {
                 if let Some((last_click_pos, last_click_time)) = &board_data.last_click {
-                    if last_click_pos.clone() == pos.clone()
-                        && *last_click_time - chrono::Utc::now()
+                    if last_click_pos == pos
+                        && chrono::Utc::now() - *last_click_time
                             < chrono::TimeDelta::milliseconds(250)
-                        && origin_cell.nearby_mines > 0
                     {
-                        let surround_cells =
-                            self.round_state.board_data.get_surround_cells(pos).clone();
-                        if origin_cell.nearby_mines
-                            == surround_cells.iter().filter(|it| it.is_flagged).count()
-                        {
-                            self.reveal_nearby_cell(pos);
-                        }
+                        self.round_state.perform(GameAction::Chord(pos.clone()));
                     }
                 }
             }
         }
         self.round_state.board_data.last_click = Some((pos.clone(), chrono::Utc::now()));
     }
-    fn reveal_cell(&mut self, pos: &CellPos) {
-        let mut cell = self.round_state.board_data.get_cell(pos);
+}
 
-        if cell.render_state == CellRenderState::Revealed {
-            return;
-        }
+#[cfg(test)]
+mod tests {
+    use egui::accesskit::{Live, Node, NodeId, Role};
 
-        if cell.is_mine {
-            self.round_state.round_state_type = crate::data::RoundState::Ended(
-                crate::data::RoundEndingType::ClickedMine(pos.clone()),
-            );
-        } else {
-            if self.round_state.round_state_type == RoundState::NotStarted {
-                self.round_state.start_time = chrono::Utc::now().timestamp() as u32;
-                self.round_state.round_state_type = RoundState::Playing;
-            }
+    use crate::data::{CellPos, CellRenderState, GameSettings, RoundData};
+    use crate::widgets::{GameBoard, GameStatusBar};
 
-            cell.render_state = CellRenderState::Revealed;
-            self.round_state.board_data.update_cells(vec![cell.clone()]);
-            if cell.nearby_mines == 0 {
-                self.reveal_nearby_cell(pos);
-            }
-        }
+    /// Runs one frame with the board and status bar of `round` and returns the nodes
+    /// AccessKit was sent.
+    fn accessibility_tree(round: &mut RoundData) -> Vec<(NodeId, Node)> {
+        let ctx = egui::Context::default();
+        ctx.enable_accesskit();
+        let input = egui::RawInput {
+            screen_rect: Some(egui::Rect::from_min_size(
+                egui::Pos2::ZERO,
+                egui::vec2(400.0, 500.0),
+            )),
+            ..Default::default()
+        };
+        let output = ctx.run(input, |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                let status = egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(400.0, 80.0));
+                ui.put(status, GameStatusBar::new(round, status));
+                let board =
+                    egui::Rect::from_min_size(egui::pos2(0.0, 100.0), egui::vec2(400.0, 400.0));
+                ui.put(board, GameBoard::new(round, board));
+            });
+        });
+        output
+            .platform_output
+            .accesskit_update
+            .expect("AccessKit is enabled")
+            .nodes
+    }
+
+    fn node(tree: &[(NodeId, Node)], id: NodeId) -> &Node {
+        tree.iter()
+            .find(|(it, _)| *it == id)
+            .map(|(_, node)| node)
+            .expect("child is in the tree")
+    }
+
+    fn with_role(tree: &[(NodeId, Node)], role: Role) -> Vec<&Node> {
+        tree.iter()
+            .map(|(_, node)| node)
+            .filter(|it| it.role() == role)
+            .collect()
+    }
+
+    /// A 4×3 board in play with a flag on the mine at the top left and three numbers
+    /// revealed next to it.
+    fn round() -> RoundData {
+        let board = crate::board_format::read("F1..\n11..\n...*\n").expect("valid board");
+        RoundData::from_board_state(board)
+    }
+
+    #[test]
+    fn double_click_on_a_number_chords_only_when_quick() {
+        let rect = egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(400.0, 300.0));
+        let settings = GameSettings::default();
+        let number = CellPos::new(0, 1);
+        let is_revealed = |round: &RoundData| {
+            round
+                .board_data
+                .get_cell(&CellPos::new(0, 2))
+                .is_some_and(|it| it.render_state == CellRenderState::Revealed)
+        };
+
+        let mut slow = round();
+        slow.board_data.last_click = Some((
+            number.clone(),
+            chrono::Utc::now() - chrono::TimeDelta::seconds(1),
+        ));
+        GameBoard::new(&mut slow, rect).handle_left_click(&number, &settings);
+        assert!(!is_revealed(&slow), "a second apart is two single clicks");
+
+        let mut quick = round();
+        let mut board = GameBoard::new(&mut quick, rect);
+        board.handle_left_click(&number, &settings);
+        board.handle_left_click(&number, &settings);
+        assert!(is_revealed(&quick), "the chord reveals the neighbours");
     }
-    fn reveal_nearby_cell(&mut self, pos: &CellPos) {
-        let surround_cells = self.round_state.board_data.get_surround_cells(pos).clone();
-        for it in &surround_cells {
-            if !it.is_flagged {
-                self.reveal_cell(&it.position);
+
+    #[test]
+    fn board_is_a_grid_of_rows_of_cells() {
+        let tree = accessibility_tree(&mut round());
+        let grids = with_role(&tree, Role::Grid);
+        let [grid] = grids.as_slice() else {
+            panic!("expected one grid, found {}", grids.len());
+        };
+        assert_eq!(grid.label(), Some("Minefield"), "grid label");
+        assert_eq!(grid.row_count(), Some(3), "row count");
+        assert_eq!(grid.column_count(), Some(4), "column count");
+
+        let rows: Vec<&Node> = grid.children().iter().map(|it| node(&tree, *it)).collect();
+        assert_eq!(
+            rows.iter().map(|it| it.row_index()).collect::<Vec<_>>(),
+            [Some(0), Some(1), Some(2)],
+            "rows in order"
+        );
+        for (index, row) in rows.iter().enumerate() {
+            assert_eq!(row.role(), Role::Row, "row {index} role");
+            let cells: Vec<&Node> = row.children().iter().map(|it| node(&tree, *it)).collect();
+            assert_eq!(cells.len(), 4, "cells in row {index}");
+            for (column, cell) in cells.iter().enumerate() {
+                assert_eq!(cell.role(), Role::Cell, "cell {index},{column} role");
+                assert_eq!(cell.row_index(), Some(index), "cell {index},{column} row");
+                assert_eq!(
+                    cell.column_index(),
+                    Some(column),
+                    "cell {index},{column} column"
+                );
             }
         }
     }
+
+    #[test]
+    fn cells_are_labelled_with_their_state() {
+        let tree = accessibility_tree(&mut round());
+        let label = |row: usize, column: usize| {
+            with_role(&tree, Role::Cell)
+                .into_iter()
+                .find(|it| it.row_index() == Some(row) && it.column_index() == Some(column))
+                .and_then(|it| it.label())
+                .map(str::to_owned)
+        };
+        assert_eq!(label(0, 0).as_deref(), Some("flagged"), "flagged cell");
+        assert_eq!(label(0, 1).as_deref(), Some("1"), "numbered cell");
+        assert_eq!(label(1, 0).as_deref(), Some("1"), "numbered cell");
+        assert_eq!(label(0, 3).as_deref(), Some("covered"), "hidden cell");
+        assert_eq!(label(2, 2).as_deref(), Some("covered"), "hidden cell");
+    }
+
+    #[test]
+    fn status_announces_the_round() {
+        let tree = accessibility_tree(&mut round());
+        let statuses = with_role(&tree, Role::Status);
+        let [status] = statuses.as_slice() else {
+            panic!("expected one status, found {}", statuses.len());
+        };
+        assert_eq!(status.live(), Some(Live::Polite), "announced politely");
+        assert_eq!(status.label(), Some("1 mines remaining"), "playing");
+
+        let board = crate::board_format::read("F1..\n11..\n...X\n").expect("valid board");
+        let tree = accessibility_tree(&mut RoundData::from_board_state(board));
+        let status = with_role(&tree, Role::Status).pop().expect("status node");
+        assert_eq!(status.label(), Some("You hit a mine"), "lost");
+
+        let board = crate::board_format::read("*100\n1111\n001*\n").expect("valid board");
+        let tree = accessibility_tree(&mut RoundData::from_board_state(board));
+        let status = with_role(&tree, Role::Status).pop().expect("status node");
+        let won = status.label().unwrap_or_default();
+        assert!(won.starts_with("You won in"), "won: {won}");
+    }
 }
@@ -1,4 +1,6 @@
-use crate::data::RoundData;
+use crate::data::{RoundData, RoundEndingType, RoundState};
+use crate::i18n::{tr, tr_args};
+use crate::widgets::SevenSegmentDisplay;
 pub struct PassedTimeIndicator {
     passed_time: u32,
 }
@@ -11,46 +13,115 @@ impl PassedTimeIndicator {
 
 impl egui::Widget for PassedTimeIndicator {
     fn ui(self, ui: &mut egui::Ui) -> egui::Response {
-        egui::Frame::NONE
-            .fill(egui::Color32::WHITE)
-            .show(ui, |ui| {
-                ui.label(format!("{}", self.passed_time));
-            })
-            .response
+        let seconds = i32::try_from(self.passed_time).unwrap_or(i32::MAX);
+        ui.add(SevenSegmentDisplay::new(seconds))
     }
 }
 
 pub struct RemainMineIndicator {
-    remain_mines: usize,
+    /// Negative once more flags are placed than there are mines.
+    remain_mines: i32,
 }
 
 impl RemainMineIndicator {
-    pub fn new(remain_mines: usize) -> Self {
+    pub fn new(remain_mines: i32) -> Self {
         Self { remain_mines }
     }
 }
 
-pub struct EmojiButton {}
+/// The smiley between the counters, which mirrors the round and restarts it when clicked.
+pub struct EmojiButton<'a> {
+    round_state: &'a RoundState,
+    /// A board cell is held down, which gets the surprised face.
+    cell_pressed: bool,
+    clickable: bool,
+}
+
+impl<'a> EmojiButton<'a> {
+    pub fn new(round_state: &'a RoundState, cell_pressed: bool, clickable: bool) -> Self {
+        Self {
+            round_state,
+            cell_pressed,
+            clickable,
+        }
+    }
+}
+
+impl egui::Widget for EmojiButton<'_> {
+    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+        let image_source: crate::app::GameImageSource = ui.data(|d| {
+            d.get_temp(egui::Id::new("IMAGE_SOURCE"))
+                .unwrap_or_default()
+        });
+        let sense = if self.clickable {
+            egui::Sense::click()
+        } else {
+            egui::Sense::hover()
+        };
+        let (rect, response) = ui.allocate_exact_size(ui.available_size(), sense);
+        let face = if response.is_pointer_button_down_on() {
+            image_source.face_pressed
+        } else {
+            match self.round_state {
+                RoundState::Ended(RoundEndingType::Victory) => image_source.face_win,
+                RoundState::Ended(RoundEndingType::ClickedMine(_)) => image_source.face_lose,
+                RoundState::NotStarted | RoundState::Playing if self.cell_pressed => {
+                    image_source.face_active
+                }
+                RoundState::NotStarted | RoundState::Playing => image_source.face_unpressed,
+            }
+        };
+        egui::Image::new(face).paint_at(ui, rect);
+        response
+    }
+}
 
 impl egui::Widget for RemainMineIndicator {
     fn ui(self, ui: &mut egui::Ui) -> egui::Response {
-        egui::Frame::NONE
-            .fill(egui::Color32::WHITE)
-            .show(ui, |ui| {
-                ui.label(format!("{}", self.remain_mines));
-            })
-            .response
+        ui.add(SevenSegmentDisplay::new(self.remain_mines))
+    }
+}
+
+/// What the status bar says to screen readers about the round.
+fn announcement(round: &RoundData) -> String {
+    match round.round_state_type {
+        RoundState::Ended(RoundEndingType::Victory) => {
+            tr_args("status.won", &[("seconds", &round.time_passed)])
+        }
+        RoundState::Ended(RoundEndingType::ClickedMine(_)) => tr("status.lost").to_owned(),
+        _ if round.is_paused() => tr("board.paused").to_owned(),
+        RoundState::NotStarted | RoundState::Playing => tr_args(
+            "status.mines_remaining",
+            &[("mines", &round.mines_remaining)],
+        ),
     }
 }
 
 pub struct GameStatusBar<'a> {
     round_state: &'a RoundData,
     rect: egui::Rect,
+    cell_pressed: bool,
+    restart: Option<&'a mut bool>,
 }
 
 impl<'a> GameStatusBar<'a> {
     pub fn new(round_state: &'a RoundData, rect: egui::Rect) -> Self {
-        Self { round_state, rect }
+        Self {
+            round_state,
+            rect,
+            cell_pressed: false,
+            restart: None,
+        }
+    }
+    /// Shows the surprised face while a board cell is held down.
+    pub fn cell_pressed(mut self, cell_pressed: bool) -> Self {
+        self.cell_pressed = cell_pressed;
+        self
+    }
+    /// Makes the face clickable; a click sets `restart`.
+    pub fn restart_button(mut self, restart: &'a mut bool) -> Self {
+        self.restart = Some(restart);
+        self
     }
 }
 impl egui::Widget for GameStatusBar<'_> {
@@ -64,10 +135,30 @@ impl egui::Widget for GameStatusBar<'_> {
             egui::pos2(self.rect.min.x + box_margin, self.rect.min.y + box_margin),
             box_size,
         );
-        ui.put(
+        let remain_mines = ui.put(
             remain_mine_widget_rect,
             RemainMineIndicator::new(self.round_state.mines_remaining),
         );
+        // Screen readers speak the counter whenever the round changes, so they hear
+        // flags placed and the round ending without looking for it.
+        ui.ctx().accesskit_node_builder(remain_mines.id, |node| {
+            node.set_role(egui::accesskit::Role::Status);
+            node.set_live(egui::accesskit::Live::Polite);
+            node.set_label(announcement(self.round_state));
+        });
+        let face_rect =
+            egui::Rect::from_center_size(self.rect.center(), egui::Vec2::splat(box_height));
+        let face = ui.put(
+            face_rect,
+            EmojiButton::new(
+                &self.round_state.round_state_type,
+                self.cell_pressed,
+                self.restart.is_some(),
+            ),
+        );
+        if let Some(restart) = self.restart {
+            *restart |= face.clicked();
+        }
         let time_widget_rect = egui::Rect::from_min_size(
             egui::pos2(
                 self.rect.min.x + self.rect.width() - box_margin - box_width,
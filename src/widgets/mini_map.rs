@@ -0,0 +1,149 @@
+use crate::data::{CellData, CellRenderState, GameBoardData};
+use crate::theme::{MiniMapColors, Theme};
+
+/// Pixels to a side of a cell large enough to show the gap around it.
+const GAPPED_CELL_PIXELS: usize = 20;
+
+/// The picture of a board last drawn by a minimap, kept in the egui memory under its id
+/// until the board, its size class or the colours change.
+#[derive(Clone)]
+struct MiniMapTexture {
+    revision: u64,
+    pixels_per_cell: usize,
+    colors: MiniMapColors,
+    texture: egui::TextureHandle,
+}
+
+/// A thumbnail of a board, one flat rectangle per cell, drawn as a single texture.
+pub struct MiniMap<'a> {
+    board: &'a GameBoardData,
+    /// Longest side in points; the other side follows the board's aspect ratio.
+    size: f32,
+    /// The part of the board in view, in cells, outlined and moved by clicking or dragging.
+    viewport: Option<egui::Rect>,
+}
+impl<'a> MiniMap<'a> {
+    pub fn new(board: &'a GameBoardData, size: f32) -> Self {
+        Self {
+            board,
+            size,
+            viewport: None,
+        }
+    }
+    pub fn viewport(mut self, viewport: egui::Rect) -> Self {
+        self.viewport = Some(viewport);
+        self
+    }
+    /// The board's picture, painted again only when it is not cached under `id`.
+    fn texture(
+        &self,
+        ui: &egui::Ui,
+        id: egui::Id,
+        pixels_per_cell: usize,
+        colors: MiniMapColors,
+    ) -> egui::TextureHandle {
+        let revision = self.board.revision();
+        if let Some(cached) = ui.data(|d| d.get_temp::<MiniMapTexture>(id)).filter(|it| {
+            it.revision == revision && it.pixels_per_cell == pixels_per_cell && it.colors == colors
+        }) {
+            return cached.texture;
+        }
+        let image = paint_image(self.board, pixels_per_cell, &colors);
+        let texture = ui
+            .ctx()
+            .load_texture("minimap", image, egui::TextureOptions::NEAREST);
+        let cached = MiniMapTexture {
+            revision,
+            pixels_per_cell,
+            colors,
+            texture: texture.clone(),
+        };
+        ui.data_mut(|d| d.insert_temp(id, cached));
+        texture
+    }
+}
+
+impl egui::Widget for MiniMap<'_> {
+    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+        let width = self.board.width().max(1);
+        let height = self.board.height().max(1);
+        let cell_size = self.size / width.max(height) as f32;
+        let sense = if self.viewport.is_some() {
+            egui::Sense::click_and_drag()
+        } else {
+            egui::Sense::hover()
+        };
+        let (rect, response) = ui.allocate_exact_size(
+            egui::vec2(width as f32 * cell_size, height as f32 * cell_size),
+            sense,
+        );
+        let painter = ui.painter_at(rect);
+        let theme = Theme::load(ui.ctx());
+        // Cells too small to tell apart are drawn a pixel each; larger ones get a gap
+        // between them.
+        let pixels_per_cell = if cell_size < 2.0 {
+            1
+        } else {
+            GAPPED_CELL_PIXELS
+        };
+        let texture = self.texture(ui, response.id, pixels_per_cell, theme.mini_map);
+        painter.image(
+            texture.id(),
+            rect,
+            egui::Rect::from_min_max(egui::Pos2::ZERO, egui::pos2(1.0, 1.0)),
+            egui::Color32::WHITE,
+        );
+        if let Some(viewport) = self.viewport {
+            let viewport = egui::Rect::from_min_max(
+                rect.min + viewport.min.to_vec2() * cell_size,
+                rect.min + viewport.max.to_vec2() * cell_size,
+            );
+            painter.rect_stroke(
+                viewport.intersect(rect),
+                0.0,
+                egui::Stroke::new(theme.outline_width(), theme.highlight),
+                egui::StrokeKind::Inside,
+            );
+        }
+        response
+    }
+}
+
+/// Paints the board into an image, `pixels_per_cell` pixels to a side of each cell.
+fn paint_image(
+    board: &GameBoardData,
+    pixels_per_cell: usize,
+    colors: &MiniMapColors,
+) -> egui::ColorImage {
+    let (width, height) = (board.width().max(1), board.height().max(1));
+    let mut image = egui::ColorImage::filled(
+        [width * pixels_per_cell, height * pixels_per_cell],
+        colors.background,
+    );
+    // A pixel of background on each side of a gapped cell.
+    let inset = usize::from(pixels_per_cell > 1);
+    for cell in board.cells.iter().flatten() {
+        let color = cell_color(cell, colors);
+        let (top, left) = (
+            cell.position.x * pixels_per_cell,
+            cell.position.y * pixels_per_cell,
+        );
+        for y in top + inset..top + pixels_per_cell - inset {
+            for x in left + inset..left + pixels_per_cell - inset {
+                if let Some(pixel) = image.pixels.get_mut(y * image.size[0] + x) {
+                    *pixel = color;
+                }
+            }
+        }
+    }
+    image
+}
+
+fn cell_color(cell: &CellData, colors: &MiniMapColors) -> egui::Color32 {
+    match (cell.render_state == CellRenderState::Revealed, cell.is_mine) {
+        (true, true) => colors.mine,
+        (true, false) => colors.revealed,
+        (false, _) if cell.is_flagged => colors.flagged,
+        (false, _) => colors.covered,
+    }
+}
@@ -0,0 +1,129 @@
+//! Vector-painted seven-segment digits, as on the classic game's counters.
+
+use crate::theme::Theme;
+
+/// Lit segments per digit, bit 0 to 6 for segments `a` (top) clockwise to `f`, then `g`
+/// (middle).
+const DIGIT_SEGMENTS: [u8; 10] = [0x3F, 0x06, 0x5B, 0x4F, 0x66, 0x6D, 0x7D, 0x07, 0x7F, 0x6F];
+const MINUS_SEGMENTS: u8 = 0x40;
+
+/// A counter in the theme's colours, black with red digits by default, showing `value`
+/// on a fixed number of digits. Negative values get a leading minus, and values that do
+/// not fit are clamped.
+pub struct SevenSegmentDisplay {
+    value: i32,
+    digits: u32,
+}
+
+impl SevenSegmentDisplay {
+    pub fn new(value: i32) -> Self {
+        Self { value, digits: 3 }
+    }
+    pub fn digits(mut self, digits: u32) -> Self {
+        self.digits = digits.max(1);
+        self
+    }
+
+    /// Segment masks from the leftmost digit.
+    fn segments(&self) -> Vec<u8> {
+        let max = 10_i32.saturating_pow(self.digits) - 1;
+        let min = -(10_i32.saturating_pow(self.digits - 1) - 1);
+        let value = self.value.clamp(min, max);
+        let digit_count = if value < 0 {
+            self.digits - 1
+        } else {
+            self.digits
+        };
+        let mut segments: Vec<u8> = (0..digit_count)
+            .rev()
+            .map(|place| {
+                let digit = value.unsigned_abs() / 10_u32.pow(place) % 10;
+                DIGIT_SEGMENTS.get(digit as usize).copied().unwrap_or(0)
+            })
+            .collect();
+        if value < 0 {
+            segments.insert(0, MINUS_SEGMENTS);
+        }
+        segments
+    }
+}
+
+impl egui::Widget for SevenSegmentDisplay {
+    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+        let (rect, response) = ui.allocate_exact_size(ui.available_size(), egui::Sense::hover());
+        let painter = ui.painter_at(rect);
+        let theme = Theme::load(ui.ctx());
+        painter.rect_filled(rect, 0.0, theme.counter);
+
+        let segments = self.segments();
+        let digit_width = rect.width() / segments.len() as f32;
+        for (index, mask) in segments.into_iter().enumerate() {
+            let digit_rect = egui::Rect::from_min_size(
+                rect.min + egui::vec2(digit_width * index as f32, 0.0),
+                egui::vec2(digit_width, rect.height()),
+            );
+            paint_digit(&painter, digit_rect, mask, &theme);
+        }
+        response
+    }
+}
+
+fn paint_digit(painter: &egui::Painter, rect: egui::Rect, mask: u8, theme: &Theme) {
+    // Keep the classic tall proportions whatever the widget's aspect ratio.
+    let height = (rect.height() * 0.8).min(rect.width() * 1.5);
+    let width = height * 0.55;
+    let thickness = width * 0.18;
+    let digit = egui::Rect::from_center_size(rect.center(), egui::vec2(width, height))
+        .shrink(thickness / 2.0);
+    let (left, right) = (digit.left(), digit.right());
+    let (top, middle, bottom) = (digit.top(), digit.center().y, digit.bottom());
+
+    let segments = [
+        horizontal(left, right, top, thickness),
+        vertical(right, top, middle, thickness),
+        vertical(right, middle, bottom, thickness),
+        horizontal(left, right, bottom, thickness),
+        vertical(left, middle, bottom, thickness),
+        vertical(left, top, middle, thickness),
+        horizontal(left, right, middle, thickness),
+    ];
+    for (bit, points) in segments.into_iter().enumerate() {
+        let color = if mask & (1 << bit) == 0 {
+            theme.counter_unlit
+        } else {
+            theme.counter_lit
+        };
+        painter.add(egui::Shape::convex_polygon(
+            points.to_vec(),
+            color,
+            egui::Stroke::NONE,
+        ));
+    }
+}
+
+/// A pointed bar along `y` from `x0` to `x1`, shortened to leave a gap at the joints.
+fn horizontal(x0: f32, x1: f32, y: f32, thickness: f32) -> [egui::Pos2; 6] {
+    let half = thickness / 2.0;
+    let (x0, x1) = (x0 + half * 0.3, x1 - half * 0.3);
+    [
+        egui::pos2(x0, y),
+        egui::pos2(x0 + half, y - half),
+        egui::pos2(x1 - half, y - half),
+        egui::pos2(x1, y),
+        egui::pos2(x1 - half, y + half),
+        egui::pos2(x0 + half, y + half),
+    ]
+}
+
+fn vertical(x: f32, y0: f32, y1: f32, thickness: f32) -> [egui::Pos2; 6] {
+    let half = thickness / 2.0;
+    let (y0, y1) = (y0 + half * 0.3, y1 - half * 0.3);
+    [
+        egui::pos2(x, y0),
+        egui::pos2(x + half, y0 + half),
+        egui::pos2(x + half, y1 - half),
+        egui::pos2(x, y1),
+        egui::pos2(x - half, y1 - half),
+        egui::pos2(x - half, y0 + half),
+    ]
+}